@@ -0,0 +1,72 @@
+//! Times CSV loading on `data/ALL_FUTURES.csv` replicated to larger sizes, comparing the
+//! sequential `csv` reader with the chunked parallel parser.
+//!
+//!     cargo bench --bench csv_load [-- <copies>...]
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use light_r::csvfile;
+use light_r::dataframe::CsvOptions;
+use light_r::DataFrame;
+use tempfile::NamedTempFile;
+
+const SOURCE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/ALL_FUTURES.csv");
+
+/// Write the header once and the body `copies` times. The index must increase, and too few
+/// days remain after the data's first date for every copy, so each row is relabelled one
+/// minute after the one before it.
+fn replicate(copies: usize) -> NamedTempFile {
+    let source = std::fs::read_to_string(SOURCE).expect("data/ALL_FUTURES.csv is readable");
+    let (header, body) = source.split_once('\n').expect("file has a header");
+    let first = body.split_once(',').expect("rows have a date").0;
+    let mut time = NaiveDate::parse_from_str(first, "%Y-%m-%d").expect("the first date is %Y-%m-%d").and_time(NaiveTime::MIN);
+    let mut file = BufWriter::new(NamedTempFile::new().unwrap());
+    writeln!(file, "{}", header).unwrap();
+    for _ in 0..copies {
+        for line in body.lines() {
+            let values = line.split_once(',').expect("rows have a date").1;
+            writeln!(file, "{},{}", time.format("%Y-%m-%d %H:%M:%S"), values).unwrap();
+            time += TimeDelta::minutes(1);
+        }
+    }
+    file.into_inner().unwrap()
+}
+
+/// Best of three runs
+fn time<F: FnMut() -> DataFrame>(mut load: F) -> (Duration, usize) {
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            let df = load();
+            (start.elapsed(), df.num_rows)
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let copies: Vec<usize> = std::env::args().skip(1).filter_map(|arg| arg.parse().ok()).collect();
+    let copies = if copies.is_empty() { vec![1, 32, 256] } else { copies };
+    let options = CsvOptions::default();
+
+    println!("{:>8} {:>10} {:>10} {:>12} {:>12} {:>8}", "copies", "MB", "rows", "sequential", "parallel", "speedup");
+    for copies in copies {
+        let file = replicate(copies);
+        let path = file.path().to_str().unwrap();
+        let megabytes = file.as_file().metadata().unwrap().len() as f64 / 1e6;
+
+        let (sequential, rows) = time(|| {
+            let reader = BufReader::new(File::open(path).unwrap());
+            DataFrame::from_csv_reader(reader, &options, "column").unwrap()
+        });
+        let (parallel, _) = time(|| csvfile::read(path, &options, "column").unwrap());
+        println!(
+            "{:>8} {:>10.1} {:>10} {:>12.3?} {:>12.3?} {:>7.1}x",
+            copies, megabytes, rows, sequential, parallel,
+            sequential.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use light_r::operations::{after, cgrep, cumsum, signal};
+use light_r::DataFrame;
+
+fn main() -> light_r::Result<()> {
+    // Load futures prices, keep the S&P 500 column from 2020 onwards
+    let df = DataFrame::new_from_csv("data/ALL_FUTURES.csv", "row")?;
+    let df = after::main(&df, "2020-01-01")?;
+    let df = cgrep::main(&df, &["SP500".to_string()])?;
+
+    // Long/short signal on the price level, accumulated from zero
+    let df = signal::main(&df)?;
+    let df = cumsum::main(&df, 0.0)?;
+
+    println!("Rows: {}, last value: {}", df.num_rows, df.get(df.num_rows - 1, 0));
+    Ok(())
+}
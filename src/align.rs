@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::index::Index;
+
+/// How to match the rows of two frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Join {
+    #[default]
+    Inner,                              // Rows in both frames
+    Left,                               // Every row of the left frame
+    Outer,                              // Rows in either frame, in index order
+    Asof,                               // Every row of the left frame, matched with the latest right row at or before it
+}
+
+impl FromStr for Join {
+    type Err = Error;
+
+    /// `inner`, `left`, `outer`, or `asof` (also `ffill`)
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "inner" => Ok(Join::Inner),
+            "left" => Ok(Join::Left),
+            "outer" => Ok(Join::Outer),
+            "asof" | "ffill" => Ok(Join::Asof),
+            _ => Err(Error::InvalidValue(format!("Unknown join '{}': expected inner, left, outer or asof", s))),
+        }
+    }
+}
+
+/// The rows of two frames matched up. For each row of the aligned index, the row of each
+/// frame it takes values from, or None where that frame has no matching row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub index: Index,
+    pub left: Vec<Option<usize>>,
+    pub right: Vec<Option<usize>>,
+}
+
+/// Match the rows of two indexes. Dates and timestamps are merged in time order, so both
+/// must be increasing; labels are matched by equality and can't be joined `asof`.
+pub fn align(left: &Index, right: &Index, join: Join) -> Result<Alignment> {
+    if left == right {
+        let rows: Vec<Option<usize>> = (0..left.len()).map(Some).collect();
+        return Ok(Alignment { index: left.clone(), left: rows.clone(), right: rows });
+    }
+    if let (Index::Timestamps(_, a), Index::Timestamps(_, b)) = (left, right) {
+        if a != b {
+            return Err(Error::Index("Cannot align timestamps in different UTC offsets".to_string()));
+        }
+    }
+    let pairs = match (left.is_temporal(), right.is_temporal()) {
+        (true, true) => merge_times(left, right, join),
+        (false, false) => match_labels(left, right, join)?,
+        _ => return Err(Error::Index("Cannot align a date index with an index of labels".to_string())),
+    };
+    let index = combine(left, right, &pairs)?;
+    let (left, right) = pairs.into_iter().unzip();
+    Ok(Alignment { index, left, right })
+}
+
+fn merge_times(left: &Index, right: &Index, join: Join) -> Vec<(Option<usize>, Option<usize>)> {
+    let time = |index: &Index, row: usize| index.time(row).expect("temporal index");
+    let (n, m) = (left.len(), right.len());
+    let mut pairs = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    if join == Join::Asof {
+        for i in 0..n {
+            let t = time(left, i);
+            while j < m && time(right, j) <= t {
+                j += 1;
+            }
+            pairs.push((Some(i), j.checked_sub(1)));
+        }
+        return pairs;
+    }
+    while i < n || (j < m && join == Join::Outer) {
+        let order = if i == n {
+            Ordering::Greater
+        } else if j == m {
+            Ordering::Less
+        } else {
+            time(left, i).cmp(&time(right, j))
+        };
+        match order {
+            Ordering::Equal => {
+                pairs.push((Some(i), Some(j)));
+                i += 1;
+                j += 1;
+            }
+            Ordering::Less => {
+                if join != Join::Inner {
+                    pairs.push((Some(i), None));
+                }
+                i += 1;
+            }
+            Ordering::Greater => {
+                if join == Join::Outer {
+                    pairs.push((None, Some(j)));
+                }
+                j += 1;
+            }
+        }
+    }
+    pairs
+}
+
+fn match_labels(left: &Index, right: &Index, join: Join) -> Result<Vec<(Option<usize>, Option<usize>)>> {
+    if join == Join::Asof {
+        return Err(Error::InvalidValue("An asof join needs a date index".to_string()));
+    }
+    let (left, right) = (left.labels(), right.labels());
+    let mut rows: HashMap<&str, usize> = HashMap::with_capacity(right.len());
+    for (row, label) in right.iter().enumerate().rev() {
+        rows.insert(label, row);
+    }
+    let mut matched = vec![false; right.len()];
+    let mut pairs = Vec::with_capacity(left.len());
+    for (i, label) in left.iter().enumerate() {
+        let j = rows.get(label.as_str()).copied();
+        if let Some(j) = j {
+            matched[j] = true;
+        }
+        if j.is_some() || join != Join::Inner {
+            pairs.push((Some(i), j));
+        }
+    }
+    if join == Join::Outer {
+        pairs.extend((0..right.len()).filter(|&j| !matched[j]).map(|j| (None, Some(j))));
+    }
+    Ok(pairs)
+}
+
+/// The aligned index, taking each row from the left index where it has one
+fn combine(left: &Index, right: &Index, pairs: &[(Option<usize>, Option<usize>)]) -> Result<Index> {
+    if let Some(rows) = pairs.iter().map(|&(row, _)| row).collect::<Option<Vec<usize>>>() {
+        return Ok(left.select(&rows));
+    }
+    fn pick<T: Clone>(a: &[T], b: &[T], pair: &(Option<usize>, Option<usize>)) -> T {
+        match *pair {
+            (Some(i), _) => a[i].clone(),
+            (None, j) => b[j.expect("an aligned row comes from one of the frames")].clone(),
+        }
+    }
+    match (left, right) {
+        (Index::Dates(a), Index::Dates(b)) => Ok(Index::Dates(pairs.iter().map(|pair| pick(a, b, pair)).collect())),
+        (Index::Timestamps(a, offset), Index::Timestamps(b, _)) => {
+            Ok(Index::Timestamps(pairs.iter().map(|pair| pick(a, b, pair)).collect(), *offset))
+        }
+        (Index::Labels(a), Index::Labels(b)) => Ok(Index::Labels(pairs.iter().map(|pair| pick(a, b, pair)).collect())),
+        _ => Err(Error::Index("Cannot combine a date index with a timestamp index".to_string())),
+    }
+}
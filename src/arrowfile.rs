@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Date32Type, Float64Type, TimestampMicrosecondType};
+use arrow_array::{Array, ArrayRef, Date32Array, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_cast::cast;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Duration, FixedOffset};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::dataframe::{DataFrame, DataFrameBuilder, IndexColumn};
+use crate::error::{Error, Result};
+use crate::index::Index;
+
+// Parquet and Arrow IPC files hold typed columns. One column becomes the row index and
+// every numeric column becomes a frame column, read as f64 with nulls as NaN. Other
+// columns (strings, booleans, nested types) are skipped.
+//
+// Frames are written with a "DATE" column typed after the index (dates, timestamps with
+// their UTC offset as the zone, or strings for labels), followed by one nullable f64
+// column per frame column with NaN written as null. Column tags are kept as field metadata.
+
+/// Columnar file formats, recognised by file extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Parquet,                            // .parquet, .pq
+    Ipc,                                // .arrow, .feather, .ipc
+}
+
+impl Format {
+    /// The format of a path, or None for anything else (such as CSV)
+    pub fn from_path(path: &str) -> Option<Format> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "parquet" | "pq" => Some(Format::Parquet),
+            "arrow" | "feather" | "ipc" => Some(Format::Ipc),
+            _ => None,
+        }
+    }
+}
+
+/// Read a Parquet or Arrow IPC file. The index is the given column if there is one, and
+/// otherwise the first date or timestamp column, falling back to the first column.
+pub fn read(path: &str, format: Format, index: Option<&IndexColumn>, row_or_column: &str) -> Result<DataFrame> {
+    let file = File::open(path)?;
+    let (schema, batches): (SchemaRef, Box<dyn Iterator<Item = std::result::Result<RecordBatch, _>>>) = match format {
+        Format::Parquet => {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+            let schema = builder.schema().clone();
+            (schema, Box::new(builder.build()?))
+        }
+        Format::Ipc => {
+            let reader = arrow_ipc::reader::FileReader::try_new(file, None)?;
+            (reader.schema(), Box::new(reader))
+        }
+    };
+
+    let fields = schema.fields();
+    let index_column = match index {
+        Some(IndexColumn::Name(name)) => schema.index_of(name).map_err(|_| Error::MissingColumn(name.clone()))?,
+        Some(IndexColumn::Position(position)) if *position < fields.len() => *position,
+        Some(IndexColumn::Position(position)) => return Err(Error::MissingColumn(format!("at position {}", position))),
+        None => fields
+            .iter()
+            .position(|field| matches!(field.data_type(), DataType::Date32 | DataType::Date64 | DataType::Timestamp(..)))
+            .or((!fields.is_empty()).then_some(0))
+            .ok_or_else(|| Error::MissingColumn("at position 0".to_string()))?,
+    };
+    let value_columns: Vec<usize> = (0..fields.len())
+        .filter(|&i| i != index_column && fields[i].data_type().is_numeric())
+        .collect();
+    let column_names = value_columns.iter().map(|&i| fields[i].name().clone()).collect();
+    let tags = value_columns.iter().map(|&i| fields[i].metadata().clone().into_iter().collect()).collect();
+
+    let batches = batches.collect::<std::result::Result<Vec<_>, _>>()?;
+    let index = read_index(&batches, index_column, fields[index_column].data_type())?;
+    index.validate()?;
+
+    let mut builder = DataFrameBuilder::new(index, column_names, row_or_column)?.tags(tags);
+    let mut first_row = 0;
+    for batch in &batches {
+        for (col, &i) in value_columns.iter().enumerate() {
+            let values = cast(batch.column(i), &DataType::Float64)?;
+            for (row, value) in values.as_primitive::<Float64Type>().iter().enumerate() {
+                builder.set(first_row + row, col, value.unwrap_or(f64::NAN));
+            }
+        }
+        first_row += batch.num_rows();
+    }
+    builder.build()
+}
+
+/// The index from one column of every batch. Arrow timestamps are UTC instants; those with
+/// a fixed-offset zone keep it. Named zones other than UTC can't be held by the index and
+/// are an error. Columns of other types are read as text and their index type inferred.
+fn read_index(batches: &[RecordBatch], column: usize, data_type: &DataType) -> Result<Index> {
+    let arrays = || batches.iter().map(|batch| batch.column(column));
+    if data_type.is_temporal() && arrays().any(|array| array.null_count() > 0) {
+        return Err(Error::Index("The index column has missing values".to_string()));
+    }
+    match data_type {
+        DataType::Date32 | DataType::Date64 => {
+            let mut dates = Vec::new();
+            for array in arrays() {
+                let days = cast(array, &DataType::Date32)?;
+                for &days in days.as_primitive::<Date32Type>().values() {
+                    let date = Date32Type::to_naive_date_opt(days)
+                        .ok_or_else(|| Error::Format(format!("Date out of range: {} days", days)))?;
+                    dates.push(date);
+                }
+            }
+            Ok(Index::Dates(dates))
+        }
+        DataType::Timestamp(_, zone) => {
+            let offset = zone.as_deref().map(parse_zone).transpose()?;
+            let shift = Duration::seconds(offset.map_or(0, |offset| offset.local_minus_utc()).into());
+            let mut times = Vec::new();
+            for array in arrays() {
+                let micros = cast(array, &DataType::Timestamp(TimeUnit::Microsecond, zone.clone()))?;
+                for &micros in micros.as_primitive::<TimestampMicrosecondType>().values() {
+                    let time = DateTime::from_timestamp_micros(micros)
+                        .ok_or_else(|| Error::Format(format!("Timestamp out of range: {}", micros)))?;
+                    times.push(time.naive_utc() + shift);
+                }
+            }
+            Ok(Index::Timestamps(times, offset))
+        }
+        _ => {
+            let mut labels = Vec::new();
+            for array in arrays() {
+                let strings = cast(array, &DataType::Utf8)?;
+                labels.extend(strings.as_string::<i32>().iter().map(|label| label.unwrap_or_default().to_string()));
+            }
+            Ok(Index::from(labels))
+        }
+    }
+}
+
+/// The UTC offset of an Arrow time zone: a fixed offset such as `+05:30`, or UTC
+fn parse_zone(zone: &str) -> Result<FixedOffset> {
+    match zone {
+        "UTC" | "Etc/UTC" | "Z" => Ok(FixedOffset::east_opt(0).unwrap()),
+        _ => zone.parse().map_err(|_| {
+            Error::Index(format!("Unsupported time zone '{}' in the index: only UTC and fixed offsets such as +05:00 are supported", zone))
+        }),
+    }
+}
+
+/// Write a frame as Parquet (Snappy-compressed) or Arrow IPC
+pub fn write(df: &DataFrame, path: &str, format: Format) -> Result<()> {
+    let batch = record_batch(df)?;
+    let file = File::create(path)?;
+    match format {
+        Format::Parquet => {
+            let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+        Format::Ipc => {
+            let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &batch.schema())?;
+            writer.write(&batch)?;
+            writer.finish()?;
+        }
+    }
+    Ok(())
+}
+
+fn record_batch(df: &DataFrame) -> Result<RecordBatch> {
+    let (index_type, index): (DataType, ArrayRef) = match &df.index {
+        Index::Dates(dates) => {
+            let days: Vec<i32> = dates.iter().map(|&date| Date32Type::from_naive_date(date)).collect();
+            (DataType::Date32, Arc::new(Date32Array::from(days)))
+        }
+        Index::Timestamps(times, offset) => {
+            let shift = Duration::seconds(offset.map_or(0, |offset| offset.local_minus_utc()).into());
+            let micros: Vec<i64> = times.iter().map(|&time| (time - shift).and_utc().timestamp_micros()).collect();
+            let zone: Option<Arc<str>> = offset.map(|offset| offset.to_string().into());
+            let array = TimestampMicrosecondArray::from(micros).with_timezone_opt(zone.clone());
+            (DataType::Timestamp(TimeUnit::Microsecond, zone), Arc::new(array))
+        }
+        Index::Labels(labels) => (DataType::Utf8, Arc::new(StringArray::from_iter_values(labels))),
+    };
+
+    let mut fields = vec![Field::new("DATE", index_type, false)];
+    let mut columns = vec![index];
+    for (col, name) in df.column_names.iter().enumerate() {
+        let metadata: HashMap<_, _> = df.tags[col].clone().into_iter().collect();
+        fields.push(Field::new(name, DataType::Float64, true).with_metadata(metadata));
+        let values: Float64Array = df.column(col).iter().map(|&x| (!x.is_nan()).then_some(x)).collect();
+        columns.push(Arc::new(values));
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?)
+}
@@ -0,0 +1,231 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime};
+use memmap2::Mmap;
+
+use crate::dataframe::DataFrame;
+use crate::error::{Error, Result};
+use crate::index::Index;
+use crate::metadata::Tags;
+
+// light-r binary format, all integers little-endian:
+//
+//   magic        8 bytes   "LIGHTR\0\0"
+//   version      u32
+//   layout       u32       0 = row, 1 = column
+//   num_rows     u64
+//   num_columns  u64
+//   index        u32       0 = dates, 1 = timestamps, 2 = labels
+//   offset       i32       UTC offset of the timestamps in seconds, i32::MIN if unknown
+//   names        column names then, for labels, row labels, each a u32 byte length
+//                followed by UTF-8
+//   tags         per column, a u32 count then each key and value written like a name
+//   rows         for dates, i64 days since 0001-01-01; for timestamps, i64 microseconds
+//                of wall-clock time since 1970-01-01
+//   padding      zero bytes up to a multiple of 8
+//   values       num_rows * num_columns f64, little-endian, in layout order
+//
+// Values are stored exactly as a DataFrame holds them, so loading maps the file and reads
+// them in place. The index is validated on loading, like that of any other file. Version 1
+// and 2 files, which have no index or offset fields and store every row as a label, have
+// their index type inferred from the labels; version 1 files, written before columns had
+// tags, are read as untagged.
+
+const MAGIC: &[u8; 8] = b"LIGHTR\0\0";
+const VERSION: u32 = 3;
+const NO_OFFSET: i32 = i32::MIN;
+
+/// Write a frame in the binary format
+pub fn write(df: &DataFrame, path: &str) -> Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    let layout: u32 = if df.row_or_column == "row" { 0 } else { 1 };
+
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&layout.to_le_bytes());
+    header.extend_from_slice(&(df.num_rows as u64).to_le_bytes());
+    header.extend_from_slice(&(df.num_columns as u64).to_le_bytes());
+    let (kind, offset): (u32, Option<FixedOffset>) = match &df.index {
+        Index::Dates(_) => (0, None),
+        Index::Timestamps(_, offset) => (1, *offset),
+        Index::Labels(_) => (2, None),
+    };
+    header.extend_from_slice(&kind.to_le_bytes());
+    header.extend_from_slice(&offset.map_or(NO_OFFSET, |offset| offset.local_minus_utc()).to_le_bytes());
+    for name in &df.column_names {
+        push_name(&mut header, name)?;
+    }
+    if let Index::Labels(labels) = &df.index {
+        for label in labels {
+            push_name(&mut header, label)?;
+        }
+    }
+    for tags in &df.tags {
+        header.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+        for (key, value) in tags {
+            push_name(&mut header, key)?;
+            push_name(&mut header, value)?;
+        }
+    }
+    match &df.index {
+        Index::Dates(dates) => {
+            for date in dates {
+                header.extend_from_slice(&i64::from(date.num_days_from_ce()).to_le_bytes());
+            }
+        }
+        Index::Timestamps(times, _) => {
+            for time in times {
+                header.extend_from_slice(&time.and_utc().timestamp_micros().to_le_bytes());
+            }
+        }
+        Index::Labels(_) => {}
+    }
+    header.resize(header.len().next_multiple_of(8), 0);
+    file.write_all(&header)?;
+
+    for value in df.values().iter() {
+        file.write_all(&value.to_le_bytes())?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Append a u32 byte length and the UTF-8 bytes of `name`
+fn push_name(header: &mut Vec<u8>, name: &str) -> Result<()> {
+    let len = u32::try_from(name.len()).map_err(|_| Error::InvalidValue(format!("Name too long: {}", name)))?;
+    header.extend_from_slice(&len.to_le_bytes());
+    header.extend_from_slice(name.as_bytes());
+    Ok(())
+}
+
+/// Memory-map a file in the binary format. Only the header is parsed; values are read in
+/// place from the mapping, so the file must not be modified while the frame is alive.
+pub fn read(path: &str) -> Result<DataFrame> {
+    let file = File::open(path)?;
+    // SAFETY: the mapping is read-only; callers must not truncate the file while it is mapped
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    let mut header = Header { bytes: &mmap, pos: 0 };
+    if header.take(8)? != MAGIC {
+        return Err(Error::Format(format!("{} is not a light-r binary file", path)));
+    }
+    let version = header.u32()?;
+    if version == 0 || version > VERSION {
+        return Err(Error::Format(format!("Unsupported binary format version {}", version)));
+    }
+    let row_or_column = match header.u32()? {
+        0 => "row",
+        1 => "column",
+        other => return Err(Error::Format(format!("Unknown layout {}", other))),
+    };
+    let num_rows = header.u64()? as usize;
+    let num_columns = header.u64()? as usize;
+    let (kind, offset) = if version >= 3 { (header.u32()?, header.i32()?) } else { (2, NO_OFFSET) };
+    let column_names = (0..num_columns).map(|_| header.name()).collect::<Result<Vec<_>>>()?;
+    let row_names = match kind {
+        2 => (0..num_rows).map(|_| header.name()).collect::<Result<Vec<_>>>()?,
+        _ => Vec::new(),
+    };
+    let tags = match version {
+        1 => vec![Tags::new(); num_columns],
+        _ => (0..num_columns).map(|_| header.tags()).collect::<Result<Vec<_>>>()?,
+    };
+    let index = match kind {
+        // Before version 3 the index type is inferred from the labels
+        2 if version < 3 => Index::from(row_names),
+        2 => Index::Labels(row_names),
+        0 => Index::Dates((0..num_rows).map(|_| header.date()).collect::<Result<_>>()?),
+        1 => {
+            let offset = match offset {
+                NO_OFFSET => None,
+                seconds => Some(FixedOffset::east_opt(seconds).ok_or_else(|| Error::Format(format!("Invalid UTC offset {}", seconds)))?),
+            };
+            Index::Timestamps((0..num_rows).map(|_| header.timestamp()).collect::<Result<_>>()?, offset)
+        }
+        other => return Err(Error::Format(format!("Unknown index type {}", other))),
+    };
+    index.validate()?;
+
+    let data_offset = header.pos.next_multiple_of(8);
+    let data_len = num_rows
+        .checked_mul(num_columns)
+        .and_then(|cells| cells.checked_mul(8))
+        .ok_or_else(|| Error::Format("Frame dimensions overflow".to_string()))?;
+    if mmap.len() < data_offset + data_len {
+        return Err(Error::Format(format!("{} is truncated", path)));
+    }
+
+    Ok(DataFrame {
+        mmap: Arc::new(mmap),
+        data_offset,
+        num_rows,
+        num_columns,
+        stride: if row_or_column == "row" { num_columns } else { num_rows },
+        column_map: None,
+        column_names,
+        tags,
+        index,
+        row_or_column: row_or_column.to_string(),
+    })
+}
+
+/// Cursor over the header bytes with bounds checks
+struct Header<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Header<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or_else(|| Error::Format("Binary header is truncated".to_string()))?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn date(&mut self) -> Result<NaiveDate> {
+        let days = self.i64()?;
+        i32::try_from(days)
+            .ok()
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+            .ok_or_else(|| Error::Format(format!("Date out of range: {} days", days)))
+    }
+
+    fn timestamp(&mut self) -> Result<NaiveDateTime> {
+        let micros = self.i64()?;
+        DateTime::from_timestamp_micros(micros)
+            .map(|time| time.naive_utc())
+            .ok_or_else(|| Error::Format(format!("Timestamp out of range: {}", micros)))
+    }
+
+    fn name(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::Format("Name is not valid UTF-8".to_string()))
+    }
+
+    fn tags(&mut self) -> Result<Tags> {
+        let count = self.u32()?;
+        (0..count).map(|_| Ok((self.name()?, self.name()?))).collect()
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs::File;
+use std::thread;
+
+use memmap2::Mmap;
+
+use crate::dataframe::{CsvOptions, DataFrame, DataFrameBuilder};
+use crate::error::{Error, Result};
+use crate::index::Index;
+
+// Files are mapped and split at newline boundaries into one chunk per worker. Each worker
+// parses its lines straight from the mapping, so fields are never copied into `String`s
+// except for the row names, and the chunks are stitched into the frame in order.
+//
+// Quoted fields may contain delimiters or newlines, which breaks splitting on raw bytes, so
+// files with any quote character go through the sequential `csv` reader instead.
+
+/// Chunks smaller than this aren't worth a thread of their own
+const MIN_CHUNK_BYTES: usize = 1 << 20;
+
+/// Read a CSV file, parsing large files on several threads
+pub fn read(path: &str, options: &CsvOptions, row_or_column: &str) -> Result<DataFrame> {
+    read_with_threads(path, options, row_or_column, crate::engine::threads())
+}
+
+/// Like `read`, with an explicit upper bound on the number of parsing threads
+pub fn read_with_threads(path: &str, options: &CsvOptions, row_or_column: &str, threads: usize) -> Result<DataFrame> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return DataFrame::from_csv_reader(file, options, row_or_column);
+    }
+    // SAFETY: the mapping is read-only and only lives for the duration of the parse
+    let mmap = unsafe { Mmap::map(&file)? };
+    let bytes = mmap.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&mmap);
+    if bytes.contains(&b'"') {
+        return DataFrame::from_csv_reader(bytes, options, row_or_column);
+    }
+
+    // The header goes through the `csv` reader so names are handled exactly as elsewhere
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let headers = rdr.headers()?.clone();
+    let (index, column_names) = options.split_header(&headers)?;
+    let position = rdr.position();
+    let (body, first_line) = (&bytes[position.byte() as usize..], position.line() as usize);
+
+    let layout = &Layout { delimiter: options.delimiter, index, fields: headers.len() };
+    let chunks = split_lines(body, threads.max(1));
+    let parsed: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = chunks.iter().map(|&chunk| scope.spawn(move || parse_chunk(chunk, layout))).collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().map_err(|_| Error::Thread("CSV parser thread panicked".to_string())))
+            .collect::<Result<_>>()
+    })?;
+
+    let mut chunk_rows = Vec::with_capacity(parsed.len());
+    let mut offset = 0;
+    for (chunk, result) in chunks.iter().zip(parsed) {
+        match result {
+            Ok(rows) => chunk_rows.push(rows),
+            Err(bad) => {
+                // Line numbers count every newline before the bad record, as the `csv` reader does
+                let line = first_line + newlines(&body[..offset + bad.byte]);
+                return Err(Error::Csv { line, message: bad.message });
+            }
+        }
+        offset += chunk.len();
+    }
+
+    let mut labels = Vec::with_capacity(chunk_rows.iter().map(|rows| rows.labels.len()).sum());
+    for rows in &mut chunk_rows {
+        labels.append(&mut rows.labels);
+    }
+    let index = Index::parse(labels, &options.index_format)?;
+    index.validate()?;
+    let (num_rows, num_columns) = (index.len(), column_names.len());
+    let mut builder = DataFrameBuilder::new(index, column_names, row_or_column)?;
+    let storage = builder.values_mut();
+    let mut row = 0;
+    for rows in chunk_rows {
+        let count = rows.values.len() / num_columns.max(1);
+        if row_or_column == "row" {
+            storage[row * num_columns..][..rows.values.len()].copy_from_slice(&rows.values);
+        } else {
+            for (i, values) in rows.values.chunks_exact(num_columns.max(1)).enumerate() {
+                for (col, &value) in values.iter().enumerate() {
+                    storage[col * num_rows + row + i] = value;
+                }
+            }
+        }
+        row += count;
+    }
+    builder.build()
+}
+
+/// How to pull a record apart
+struct Layout {
+    delimiter: u8,
+    index: usize,                       // Position of the row name field
+    fields: usize,                      // Fields per record, from the header
+}
+
+/// Row labels and row-major values of one chunk
+struct Rows {
+    labels: Vec<String>,
+    values: Vec<f64>,
+}
+
+/// A malformed record, located by its byte offset within the chunk
+struct BadRecord {
+    byte: usize,
+    message: String,
+}
+
+/// Split `body` into at most `parts` pieces, each ending just after a newline (or at the end)
+fn split_lines(body: &[u8], parts: usize) -> Vec<&[u8]> {
+    let parts = parts.min(body.len() / MIN_CHUNK_BYTES).max(1);
+    let target = body.len() / parts;
+    let mut chunks = Vec::with_capacity(parts);
+    let mut rest = body;
+    while chunks.len() + 1 < parts && rest.len() > target {
+        let end = match rest[target..].iter().position(|&b| b == b'\n') {
+            Some(newline) => target + newline + 1,
+            None => break,
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+fn parse_chunk(chunk: &[u8], layout: &Layout) -> std::result::Result<Rows, BadRecord> {
+    let estimate = newlines(chunk) + 1;
+    let mut rows = Rows {
+        labels: Vec::with_capacity(estimate),
+        values: Vec::with_capacity(estimate * layout.fields.saturating_sub(1)),
+    };
+    let mut start = 0;
+    for line in chunk.split_inclusive(|&b| b == b'\n') {
+        let record = line.strip_suffix(b"\n").unwrap_or(line);
+        let record = record.strip_suffix(b"\r").unwrap_or(record);
+        // Blank lines are skipped, as the `csv` reader does
+        if !record.is_empty() {
+            let mut fields = 0;
+            for (i, field) in record.split(|&b| b == layout.delimiter).enumerate() {
+                let field = field.trim_ascii();
+                if i == layout.index {
+                    rows.labels.push(String::from_utf8_lossy(field).into_owned());
+                } else {
+                    rows.values.push(parse_f64(field));
+                }
+                fields += 1;
+            }
+            if fields != layout.fields {
+                return Err(BadRecord {
+                    byte: start,
+                    message: format!("found record with {} fields, but the header has {} fields", fields, layout.fields),
+                });
+            }
+        }
+        start += line.len();
+    }
+    Ok(rows)
+}
+
+/// Parse a trimmed field; empty or non-numeric fields become NaN
+fn parse_f64(field: &[u8]) -> f64 {
+    fast_float2::parse(field).unwrap_or(f64::NAN)
+}
+
+fn newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| b == b'\n').count()
+}
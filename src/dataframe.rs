@@ -1,236 +1,344 @@
 use memmap2::{Mmap, MmapMut};
-use std::{fs::File, io, sync::Arc};
+use std::{borrow::Cow, io, sync::Arc};
 use core::f64;
-use tempfile::tempfile;
-use std::io::Read;
+use std::io::BufRead;
+use crate::error::{Error, Result};
+use crate::index::{Index, IndexFormat};
+use crate::metadata::Tags;
+
+/// Where the row index (dates) sits in a CSV header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexColumn {
+    Position(usize),    // 0-based field position
+    Name(String),       // Header name, e.g. "DATE"
+}
+
+impl std::str::FromStr for IndexColumn {
+    type Err = std::convert::Infallible;
+
+    /// A number is a position, anything else a header name
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s.parse::<usize>() {
+            Ok(position) => IndexColumn::Position(position),
+            Err(_) => IndexColumn::Name(s.to_string()),
+        })
+    }
+}
+
+/// How to read CSV input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub index: Option<IndexColumn>,     // None for the first column, or a file's first date column
+    pub index_format: IndexFormat,
+}
+
+impl Default for CsvOptions {
+    /// Comma separated, with the dates in the first column
+    fn default() -> Self {
+        CsvOptions { delimiter: b',', index: None, index_format: IndexFormat::Auto }
+    }
+}
+
+impl CsvOptions {
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_index(mut self, index: IndexColumn) -> Self {
+        self.index = Some(index);
+        self
+    }
 
+    pub fn with_index_format(mut self, index_format: IndexFormat) -> Self {
+        self.index_format = index_format;
+        self
+    }
+
+    /// Position of the index column and the names of the value columns
+    pub(crate) fn split_header(&self, headers: &csv::StringRecord) -> Result<(usize, Vec<String>)> {
+        if headers.iter().all(str::is_empty) {
+            return Err(Error::NoInput("CSV input has no header".to_string()));
+        }
+        let index = match self.index.as_ref().unwrap_or(&IndexColumn::Position(0)) {
+            IndexColumn::Position(position) if *position < headers.len() => *position,
+            IndexColumn::Position(position) => return Err(Error::MissingColumn(format!("at position {}", position))),
+            IndexColumn::Name(name) => headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| Error::MissingColumn(name.clone()))?,
+        };
+        let column_names = headers
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != index)
+            .map(|(_, name)| name.to_string())
+            .collect();
+        Ok((index, column_names))
+    }
+}
+
+// Values are read in place as `f64`, which relies on the mapping holding little-endian floats
+#[cfg(not(target_endian = "little"))]
+compile_error!("light-r reads little-endian f64 values in place and needs a little-endian target");
+
+#[derive(Clone)]
 pub struct DataFrame {
-    pub mmap: Arc<Mmap>,                     // Memory-mapped file
+    pub mmap: Arc<Mmap>,                // Page-aligned mapping holding the f64 values
+    pub data_offset: usize,             // Byte offset of the first value, a multiple of 8
     pub num_rows: usize,                // Number of rows in the dataset
     pub num_columns: usize,             // Number of columns in the dataset
+    pub stride: usize,                  // Values from one row start ("row" layout) or column start ("column" layout) to the next
+    pub column_map: Option<Vec<usize>>, // Stored column of each column in a column view; None when stored in order
     pub column_names: Vec<String>,      // Names of the columns
-    pub row_names: Vec<String>,         // Names of the rows (e.g., dates)
+    pub tags: Vec<Tags>,                // Metadata of each column, such as asset class or currency
+    pub index: Index,                   // Row index, usually dates
     pub row_or_column: String,          // Either "row" or "column"
-    pub offsets: Vec<Vec<usize>>,       // Byte offsets for column-wise access
 }
 
 impl DataFrame {
-    /// Create a memory-mapped DataFrame from a CSV file
-    pub fn new_from_csv(file_path: &str, row_or_column: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = File::open(file_path)?;
-        let mmap = unsafe { Mmap::map(&file)? };
-
-        let mut num_rows = 0;
-        let mut num_columns = 0;
-        let mut column_names = Vec::new();
-        let mut row_names = Vec::new(); // For storing dates or row identifiers
-        let mut raw_data = Vec::new();
-
-        let mut line_start = 0;
-
-        for (i, &byte) in mmap.iter().enumerate() {
-            if byte == b'\n' {
-                let line = &mmap[line_start..i];
-
-                if num_rows == 0 {
-                    // Parse header row
-                    let mut fields = line.split(|&b| b == b',');
-                    let first_field = String::from_utf8_lossy(fields.next().unwrap()).to_string();
-
-                    if first_field != "DATE" {
-                        return Err("Error: First column must be 'DATE'.".into());
-                    }
-
-                    num_columns = fields.clone().count();
-                    column_names = fields
-                        .map(|col| String::from_utf8_lossy(col).to_string())
-                        .collect();
-                } else {
-                    // Parse data rows
-                    let mut fields = line.split(|&b| b == b',');
-                    let date = String::from_utf8_lossy(fields.next().unwrap()).to_string();
-                    row_names.push(date); // Store the "DATE" value
-
-                    raw_data.extend(fields.map(|field| {
-                        let field_str = String::from_utf8_lossy(field).to_string().trim().to_string();
-                        if field_str.is_empty() {
-                            f64::NAN
-                        } else {
-                            field_str.parse::<f64>().unwrap_or(f64::NAN)
-                        }
-                    }));
-                }
-                num_rows += 1;
-                line_start = i + 1;
-            }
-        }
+    /// Create a memory-mapped DataFrame from a CSV file whose first column holds the dates
+    pub fn new_from_csv(file_path: &str, row_or_column: &str) -> Result<Self> {
+        Self::new_from_csv_with(file_path, &CsvOptions::default(), row_or_column)
+    }
 
-        if raw_data.len() != num_rows * num_columns {
-            return Err("Error: Mismatched row or column count.".into());
-        }
+    /// Create a memory-mapped DataFrame from a CSV file with a custom delimiter or index column
+    pub fn new_from_csv_with(file_path: &str, options: &CsvOptions, row_or_column: &str) -> Result<Self> {
+        crate::csvfile::read(file_path, options, row_or_column)
+    }
 
-        let data_size = num_rows * num_columns * 8;
-        let mut mmap_data = { MmapMut::map_anon(data_size)? };
+    /// Create a memory-mapped DataFrame from stdin
+    pub fn from_stdin(row_or_column: &str) -> Result<Self> {
+        Self::from_stdin_with(&CsvOptions::default(), row_or_column)
+    }
 
-        mmap_data.copy_from_slice(&raw_data.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>());
+    /// Create a memory-mapped DataFrame from stdin with a custom delimiter or index column
+    pub fn from_stdin_with(options: &CsvOptions, row_or_column: &str) -> Result<Self> {
+        // Records are parsed as they arrive, so the input is never held in memory as text
+        let mut stdin = io::stdin().lock();
+        if stdin.fill_buf()?.is_empty() {
+            return Err(Error::NoInput("No input provided via stdin".to_string()));
+        }
+        Self::from_csv_reader(stdin, options, row_or_column)
+    }
 
-        let offsets = Self::calc_offsets(num_rows, num_columns);
+    /// Parse CSV from any reader. Handles quoted fields, CRLF line endings, a UTF-8 BOM and a
+    /// last line without a newline. Empty or non-numeric cells become NaN.
+    pub fn from_csv_reader<R: io::Read>(reader: R, options: &CsvOptions, row_or_column: &str) -> Result<Self> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
 
-        Ok(Self {
-            mmap: Arc::new(mmap_data.make_read_only()?),
-            num_rows,
-            num_columns,
-            column_names,
-            row_names,
-            row_or_column: row_or_column.to_string(),
-            offsets: offsets,
-        })
+        // Parse headers
+        let headers = rdr.headers()?.clone();
+        let (index, column_names) = options.split_header(&headers)?;
+
+        // Stream rows into the frame; the reader rejects records whose field count differs
+        // from the header
+        let mut builder = DataFrameBuilder::with_columns(column_names, row_or_column)?.index_format(options.index_format.clone());
+        let mut record = csv::StringRecord::new();
+        let mut row = Vec::with_capacity(headers.len().saturating_sub(1));
+        while rdr.read_record(&mut record)? {
+            row.clear();
+            row.extend(
+                record
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != index)
+                    .map(|(_, value)| value.parse::<f64>().unwrap_or(f64::NAN)),
+            );
+            builder.push_row(record[index].to_string(), &row)?;
+        }
+        let df = builder.build()?;
+        df.index.validate()?;
+        Ok(df)
     }
 
-     /// Create a memory-mapped DataFrame from stdin
-     pub fn from_stdin(row_or_column: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let stdin = io::stdin();
-        let mut buffer = String::new();
-
-        stdin.lock().read_to_string(&mut buffer)?;
-        if buffer.trim().is_empty() {
-            return Err("Error: No input provided via stdin.".into());
+    /// Create a memory-mapped DataFrame from row-major values, stored in the requested layout
+    pub fn from_row_major(
+        values: &[f64],
+        num_rows: usize,
+        num_columns: usize,
+        column_names: Vec<String>,
+        index: impl Into<Index>,
+        row_or_column: &str,
+    ) -> Result<Self> {
+        let index = index.into();
+        if values.len() != num_rows * num_columns
+            || column_names.len() != num_columns
+            || index.len() != num_rows
+        {
+            return Err(Error::Shape("Mismatched row or column count".to_string()));
         }
 
-        // Parse headers
-        let mut rdr = csv::Reader::from_reader(buffer.as_bytes());
-        let headers = rdr.headers()?.clone();
-        let mut column_names: Vec<String> = headers.iter().map(String::from).collect();
-        if column_names[0] != "DATE" {
-            return Err("Error: First column must be 'DATE'.".into());
+        let mut builder = DataFrameBuilder::new(index, column_names, row_or_column)?;
+        for (i, &value) in values.iter().enumerate() {
+            builder.set(i / num_columns, i % num_columns, value);
         }
-        column_names.remove(0); // Remove "DATE" column
+        builder.build()
+    }
 
+    /// Create a memory-mapped DataFrame from a vector of rows
+    pub fn from_rows(
+        index: impl Into<Index>,
+        column_names: Vec<String>,
+        rows: Vec<Vec<f64>>,
+        row_or_column: &str,
+    ) -> Result<Self> {
+        let num_rows = rows.len();
         let num_columns = column_names.len();
-        let mut row_names = Vec::new();
-        let mut num_rows = 0;
-
-        // First pass to count rows
-        for result in rdr.records() {
-            let record = result?;
-            row_names.push(record[0].to_string()); // Store the date
-            num_rows += 1;
+        if rows.iter().any(|row| row.len() != num_columns) {
+            return Err(Error::Shape("Mismatched row or column count".to_string()));
         }
+        let values: Vec<f64> = rows.into_iter().flatten().collect();
+        Self::from_row_major(&values, num_rows, num_columns, column_names, index, row_or_column)
+    }
 
-        // Calculate the required file size
-        let file_size = num_rows * num_columns * 8;
-
-        // Create a temporary file
-        let tmpfile = tempfile()?;
-        tmpfile.set_len(file_size as u64)?; // Set the file size
-
-        // Memory-map the file
-        let mut mmap = unsafe { MmapMut::map_mut(&tmpfile)? };
-
-        // Second pass to write data directly to memory map
-        rdr = csv::Reader::from_reader(buffer.as_bytes());
-        for (row_index, result) in rdr.records().enumerate() {
-            let record = result?;
-            for (col_index, value) in record.iter().skip(1).enumerate() {
-                let parsed_value = if value.trim().is_empty() {
-                    f64::NAN
-                } else {
-                    value.parse::<f64>().unwrap_or(f64::NAN)
-                };
-
-                let offset = (row_index * num_columns + col_index) * 8;
-                mmap[offset..offset + 8].copy_from_slice(&parsed_value.to_le_bytes());
-            }
+    /// All values in storage order: row after row in "row" layout, column after column
+    /// in "column" layout. Borrowed from the mapping unless the frame is a view whose
+    /// values aren't back to back.
+    pub fn values(&self) -> Cow<'_, [f64]> {
+        let packed = if self.row_or_column == "row" { self.num_columns } else { self.num_rows };
+        if self.column_map.is_none() && (self.stride == packed || self.num_rows * self.num_columns == 0) {
+            return Cow::Borrowed(&self.storage()[..self.num_rows * self.num_columns]);
         }
-
-        // Remap to column-major order if needed
-        let remapped_mmap = if row_or_column == "column" {
-            Self::remap_to_column_major(&mmap, num_rows, num_columns)?
+        if self.row_or_column == "row" {
+            Cow::Owned((0..self.num_rows).flat_map(|row_index| self.row(row_index)).collect())
         } else {
-            mmap
-        };
+            Cow::Owned((0..self.num_columns).flat_map(|col_index| self.column(col_index).into_owned()).collect())
+        }
+    }
+
+    /// The mapped values from the first cell of the frame onwards, including any that lie
+    /// outside a view
+    fn storage(&self) -> &[f64] {
+        let len = (self.mmap.len() - self.data_offset) / 8;
+        as_f64(&self.mmap[self.data_offset..self.data_offset + len * 8])
+    }
 
-        // Calculate offsets
-        let offsets = if row_or_column == "row" {
-            Self::calc_row_offsets(num_rows, num_columns)
+    /// Stored column of a column of the frame
+    fn stored_column(&self, col_index: usize) -> usize {
+        self.column_map.as_ref().map_or(col_index, |map| map[col_index])
+    }
+
+    /// Position of the value at (row, col) within the mapped storage
+    pub(crate) fn position(&self, row_index: usize, col_index: usize) -> usize {
+        assert!(
+            row_index < self.num_rows && col_index < self.num_columns,
+            "cell ({}, {}) out of bounds for a {}x{} frame",
+            row_index, col_index, self.num_rows, self.num_columns
+        );
+        let col_index = self.stored_column(col_index);
+        if self.row_or_column == "row" {
+            row_index * self.stride + col_index
         } else {
-            Self::calc_column_offsets(num_rows, num_columns)
-        };
+            col_index * self.stride + row_index
+        }
+    }
 
-        Ok(Self {
-            mmap: Arc::new(remapped_mmap.make_read_only()?),
-            num_rows,
-            num_columns,
-            column_names,
-            row_names,
-            row_or_column: row_or_column.to_string(),
-            offsets,
-        })
+    /// Read the value at (row, col) regardless of layout
+    pub fn get(&self, row_index: usize, col_index: usize) -> f64 {
+        self.storage()[self.position(row_index, col_index)]
     }
 
-    /// Calculate row-major offsets
-    pub fn calc_row_offsets(num_rows: usize, num_columns: usize) -> Vec<Vec<usize>> {
-        (0..num_rows)
-            .map(|row_index| {
-                (0..num_columns)
-                    .map(|col_index| (row_index * num_columns + col_index) * 8)
-                    .collect()
-            })
-            .collect()
+    /// Read a whole row, left to right
+    pub fn row(&self, row_index: usize) -> Vec<f64> {
+        if self.row_or_column == "row" && self.column_map.is_none() {
+            let start = row_index * self.stride;
+            self.storage()[start..start + self.num_columns].to_vec()
+        } else {
+            (0..self.num_columns).map(|col_index| self.get(row_index, col_index)).collect()
+        }
     }
 
-    /// Calculate col-major offsets
-    pub fn calc_column_offsets(num_rows: usize, num_columns: usize) -> Vec<Vec<usize>> {
-        (0..num_columns)
-            .map(|col_index| {
-                (0..num_rows)
-                    .map(|row_index| (row_index * 8) + (col_index * num_rows * 8))
-                    .collect()
-            })
-            .collect()
+    /// Read a whole column, top to bottom. Borrowed straight from the mapping in "column"
+    /// layout, copied in "row" layout.
+    pub fn column(&self, col_index: usize) -> Cow<'_, [f64]> {
+        assert!(col_index < self.num_columns, "column {} out of bounds for {} columns", col_index, self.num_columns);
+        let stored = self.stored_column(col_index);
+        if self.row_or_column == "row" {
+            Cow::Owned(self.storage().iter().skip(stored).step_by(self.stride).take(self.num_rows).copied().collect())
+        } else {
+            let start = stored * self.stride;
+            Cow::Borrowed(&self.storage()[start..start + self.num_rows])
+        }
     }
 
-    fn remap_to_column_major(
-        mmap: &MmapMut,
-        num_rows: usize,
-        num_columns: usize,
-    ) -> Result<MmapMut, Box<dyn std::error::Error>> {
-        let data_size = num_rows * num_columns * 8;
-        let mut column_major_data = vec![0u8; data_size];
-    
-        for col_index in 0..num_columns {
-            for row_index in 0..num_rows {
-                let row_major_offset = (row_index * num_columns + col_index) * 8;
-                let column_major_offset = (col_index * num_rows + row_index) * 8;
-                column_major_data[column_major_offset..column_major_offset + 8]
-                    .copy_from_slice(&mmap[row_major_offset..row_major_offset + 8]);
-            }
+    /// The rows in `rows` as a view sharing this frame's mapping; no values are copied
+    pub fn slice_rows(&self, rows: std::ops::Range<usize>) -> Self {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.num_rows,
+            "rows {:?} out of bounds for {} rows", rows, self.num_rows
+        );
+        let offset = if self.row_or_column == "row" { rows.start * self.stride } else { rows.start };
+        DataFrame {
+            mmap: Arc::clone(&self.mmap),
+            data_offset: self.data_offset + offset * 8,
+            num_rows: rows.len(),
+            num_columns: self.num_columns,
+            stride: self.stride,
+            column_map: self.column_map.clone(),
+            column_names: self.column_names.clone(),
+            tags: self.tags.clone(),
+            index: self.index.slice(rows),
+            row_or_column: self.row_or_column.clone(),
         }
-    
-        let mut remapped_mmap = MmapMut::map_anon(data_size)?;
-        remapped_mmap.copy_from_slice(&column_major_data);
-    
-        Ok(remapped_mmap)
-    }    
-
-    /// Calculate byte offsets for each data element in each row
-    pub fn calc_offsets(num_rows: usize, num_columns: usize) -> Vec<Vec<usize>> {
-        let mut offsets = Vec::with_capacity(num_rows);
-
-        // For each row, calculate the offsets for all columns
-        for row in 0..num_rows {
-            let mut row_offsets = Vec::with_capacity(num_columns);
-            for col in 0..num_columns {
-                let offset = ((row * 2)  + col) * 8; // 8 bytes per float value
-                row_offsets.push(offset);
-            }
-            offsets.push(row_offsets);
+    }
+
+    /// The given columns, in the given order, as a view sharing this frame's mapping
+    pub fn select_columns(&self, columns: &[usize]) -> Self {
+        for &col_index in columns {
+            assert!(col_index < self.num_columns, "column {} out of bounds for {} columns", col_index, self.num_columns);
+        }
+        DataFrame {
+            mmap: Arc::clone(&self.mmap),
+            data_offset: self.data_offset,
+            num_rows: self.num_rows,
+            num_columns: columns.len(),
+            stride: self.stride,
+            column_map: Some(columns.iter().map(|&col_index| self.stored_column(col_index)).collect()),
+            column_names: columns.iter().map(|&col_index| self.column_names[col_index].clone()).collect(),
+            tags: columns.iter().map(|&col_index| self.tags[col_index].clone()).collect(),
+            index: self.index.clone(),
+            row_or_column: self.row_or_column.clone(),
+        }
+    }
+
+    /// Apply `f` to every value, keeping shape, names and layout
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Result<Self> {
+        let mut builder = DataFrameBuilder::like(self)?;
+        for (out, &value) in builder.values_mut().iter_mut().zip(self.values().iter()) {
+            *out = f(value);
         }
+        builder.build()
+    }
 
-        offsets
+    /// Build a new DataFrame with the same shape, names and tags from row-major values
+    pub fn with_values(&self, values: &[f64]) -> Result<Self> {
+        let df = Self::from_row_major(
+            values,
+            self.num_rows,
+            self.num_columns,
+            self.column_names.clone(),
+            self.index.clone(),
+            &self.row_or_column,
+        )?;
+        Ok(DataFrame { tags: self.tags.clone(), ..df })
     }
 
     pub fn print(&self) {
+        self.print_rows(0..self.num_rows);
+    }
+
+    /// Print the first `n` rows
+    pub fn print_head(&self, n: usize) {
+        self.print_rows(0..n.min(self.num_rows));
+    }
+
+    /// Print a range of rows as CSV, including the header
+    pub fn print_rows(&self, rows: std::ops::Range<usize>) {
         let mut wtr = csv::Writer::from_writer(std::io::stdout());
     
         // Write the header row
@@ -241,41 +349,14 @@ impl DataFrame {
             return;
         }
     
-        if self.row_or_column == "row" {
-            // Process row-major format
-            for row_index in 0..self.num_rows {
-                let mut record = vec![self.row_names[row_index].clone()];
-                record.extend((0..self.num_columns).map(|col_index| {
-                    let offset = self.offsets[row_index][col_index];
-                    let bytes = &self.mmap[offset..offset + 8];
-                    let value = f64::from_le_bytes(bytes.try_into().unwrap_or_default());
-                    value.to_string()
-                }));
-    
-                if let Err(err) = wtr.write_record(&record) {
-                    eprintln!("Error writing record for row {}: {}", row_index, err);
-                    return;
-                }
-            }
-        } else if self.row_or_column == "column" {
-            // Process column-major format
-            for row_index in 0..self.num_rows {
-                let mut record = vec![self.row_names[row_index].clone()];
-                record.extend((0..self.num_columns).map(|col_index| {
-                    let offset = self.offsets[col_index][row_index];
-                    let bytes = &self.mmap[offset..offset + 8];
-                    let value = f64::from_le_bytes(bytes.try_into().unwrap_or_default());
-                    value.to_string()
-                }));
-    
-                if let Err(err) = wtr.write_record(&record) {
-                    eprintln!("Error writing record for row {}: {}", row_index, err);
-                    return;
-                }
+        for row_index in rows {
+            let mut record = vec![self.index.label(row_index)];
+            record.extend(self.row(row_index).iter().map(|value| value.to_string()));
+
+            if let Err(err) = wtr.write_record(&record) {
+                eprintln!("Error writing record for row {}: {}", row_index, err);
+                return;
             }
-        } else {
-            eprintln!("Error: Unknown row_or_column format.");
-            return;
         }
     
         if let Err(err) = wtr.flush() {
@@ -284,3 +365,200 @@ impl DataFrame {
     }
 
 }
+
+/// View little-endian f64 bytes as floats without copying
+fn as_f64(bytes: &[u8]) -> &[f64] {
+    // SAFETY: every bit pattern is a valid f64, and the target is little-endian (checked above)
+    let (prefix, values, suffix) = unsafe { bytes.align_to::<f64>() };
+    assert!(prefix.is_empty() && suffix.is_empty(), "DataFrame values must be 8-byte aligned");
+    values
+}
+
+fn as_f64_mut(bytes: &mut [u8]) -> &mut [f64] {
+    // SAFETY: as for `as_f64`
+    let (prefix, values, suffix) = unsafe { bytes.align_to_mut::<f64>() };
+    assert!(prefix.is_empty() && suffix.is_empty(), "DataFrame values must be 8-byte aligned");
+    values
+}
+
+/// Position of the value at (row, col) in storage of the given layout
+fn cell_index(row_major: bool, num_rows: usize, num_columns: usize, row_index: usize, col_index: usize) -> usize {
+    if row_major {
+        row_index * num_columns + col_index
+    } else {
+        col_index * num_rows + row_index
+    }
+}
+
+/// Writes values straight into the storage of a new DataFrame in its target layout. A builder
+/// either has a fixed index filled with `set`, or starts empty and grows with `push_row`, in
+/// which case the pushed labels are parsed into an index by `build`.
+pub struct DataFrameBuilder {
+    mmap: MmapMut,
+    num_rows: usize,
+    num_columns: usize,
+    row_capacity: usize,                // Rows the storage has room for; the column stride in "column" layout
+    column_names: Vec<String>,
+    tags: Vec<Tags>,
+    index: Option<Index>,               // The fixed index, if not built from pushed labels
+    labels: Vec<String>,                // Labels of pushed rows
+    index_format: IndexFormat,          // How to parse pushed labels
+    row_or_column: String,
+}
+
+impl DataFrameBuilder {
+    /// A builder for a frame with the given names in "row" or "column" layout; every value
+    /// starts as NaN
+    pub fn new(index: impl Into<Index>, column_names: Vec<String>, row_or_column: &str) -> Result<Self> {
+        let index = index.into();
+        let mut builder = Self::with_capacity(column_names, index.len(), row_or_column)?;
+        as_f64_mut(&mut builder.mmap).fill(f64::NAN);
+        builder.num_rows = index.len();
+        builder.index = Some(index);
+        Ok(builder)
+    }
+
+    /// An empty builder to be filled row by row with `push_row`
+    pub fn with_columns(column_names: Vec<String>, row_or_column: &str) -> Result<Self> {
+        Self::with_capacity(column_names, 0, row_or_column)
+    }
+
+    /// Parse pushed labels with `index_format` rather than inferring the index type
+    pub fn index_format(mut self, index_format: IndexFormat) -> Self {
+        self.index_format = index_format;
+        self
+    }
+
+    /// Tag the columns, one set of tags per column
+    pub fn tags(mut self, tags: Vec<Tags>) -> Self {
+        assert_eq!(tags.len(), self.num_columns, "one set of tags is needed per column");
+        self.tags = tags;
+        self
+    }
+
+    fn with_capacity(column_names: Vec<String>, row_capacity: usize, row_or_column: &str) -> Result<Self> {
+        if row_or_column != "row" && row_or_column != "column" {
+            return Err(Error::InvalidValue(format!("Unknown row_or_column format '{}'", row_or_column)));
+        }
+        let num_columns = column_names.len();
+        Ok(Self {
+            mmap: MmapMut::map_anon(row_capacity * num_columns * 8)?,
+            num_rows: 0,
+            num_columns,
+            row_capacity,
+            tags: vec![Tags::new(); num_columns],
+            column_names,
+            index: None,
+            labels: Vec::new(),
+            index_format: IndexFormat::Auto,
+            row_or_column: row_or_column.to_string(),
+        })
+    }
+
+    /// A builder with the same names, tags and layout as an existing frame
+    pub fn like(df: &DataFrame) -> Result<Self> {
+        Ok(Self::new(df.index.clone(), df.column_names.clone(), &df.row_or_column)?.tags(df.tags.clone()))
+    }
+
+    fn position(&self, row_index: usize, col_index: usize) -> usize {
+        assert!(
+            row_index < self.num_rows && col_index < self.num_columns,
+            "cell ({}, {}) out of bounds for a {}x{} frame",
+            row_index, col_index, self.num_rows, self.num_columns
+        );
+        cell_index(self.row_or_column == "row", self.row_capacity, self.num_columns, row_index, col_index)
+    }
+
+    /// Write the value at (row, col)
+    pub fn set(&mut self, row_index: usize, col_index: usize, value: f64) {
+        let position = self.position(row_index, col_index);
+        as_f64_mut(&mut self.mmap)[position] = value;
+    }
+
+    /// Append a row, growing the storage as needed. Only for builders made by `with_columns`.
+    pub fn push_row(&mut self, label: String, values: &[f64]) -> Result<()> {
+        assert!(self.index.is_none(), "push_row needs a builder made by with_columns");
+        if values.len() != self.num_columns {
+            return Err(Error::Shape(format!(
+                "Row {} has {} values, expected {}",
+                label, values.len(), self.num_columns
+            )));
+        }
+        if self.num_rows == self.row_capacity {
+            self.grow((self.row_capacity * 2).max(1024))?;
+        }
+        self.num_rows += 1;
+        self.labels.push(label);
+        for (col_index, &value) in values.iter().enumerate() {
+            self.set(self.num_rows - 1, col_index, value);
+        }
+        Ok(())
+    }
+
+    /// Move the values into storage with room for `row_capacity` rows
+    fn grow(&mut self, row_capacity: usize) -> Result<()> {
+        let mut mmap = MmapMut::map_anon(row_capacity * self.num_columns * 8)?;
+        let (old, new) = (as_f64(&self.mmap), as_f64_mut(&mut mmap));
+        if self.row_or_column == "row" {
+            let used = self.num_rows * self.num_columns;
+            new[..used].copy_from_slice(&old[..used]);
+        } else {
+            for col_index in 0..self.num_columns {
+                let (from, to) = (col_index * self.row_capacity, col_index * row_capacity);
+                new[to..to + self.num_rows].copy_from_slice(&old[from..from + self.num_rows]);
+            }
+        }
+        self.mmap = mmap;
+        self.row_capacity = row_capacity;
+        Ok(())
+    }
+
+    /// All values in storage order, as for `DataFrame::values`
+    pub(crate) fn values_mut(&mut self) -> &mut [f64] {
+        debug_assert_eq!(self.num_rows, self.row_capacity, "values_mut needs a builder with fixed rows");
+        as_f64_mut(&mut self.mmap)
+    }
+
+    /// Write a whole column, top to bottom
+    pub fn set_column(&mut self, col_index: usize, values: &[f64]) {
+        assert!(col_index < self.num_columns, "column {} out of bounds for {} columns", col_index, self.num_columns);
+        assert_eq!(values.len(), self.num_rows, "column length must match the number of rows");
+        let (row_capacity, num_columns) = (self.row_capacity, self.num_columns);
+        let storage = as_f64_mut(&mut self.mmap);
+        if self.row_or_column == "row" {
+            for (cell, &value) in storage.iter_mut().skip(col_index).step_by(num_columns).zip(values) {
+                *cell = value;
+            }
+        } else {
+            let start = col_index * row_capacity;
+            storage[start..start + values.len()].copy_from_slice(values);
+        }
+    }
+
+    pub fn build(mut self) -> Result<DataFrame> {
+        // Close the gaps left by spare capacity so columns sit back to back
+        if self.row_or_column == "column" && self.row_capacity != self.num_rows {
+            let storage = as_f64_mut(&mut self.mmap);
+            for col_index in 1..self.num_columns {
+                let from = col_index * self.row_capacity;
+                storage.copy_within(from..from + self.num_rows, col_index * self.num_rows);
+            }
+        }
+        let index = match self.index {
+            Some(index) => index,
+            None => Index::parse(self.labels, &self.index_format)?,
+        };
+        Ok(DataFrame {
+            mmap: Arc::new(self.mmap.make_read_only()?),
+            data_offset: 0,
+            num_rows: self.num_rows,
+            num_columns: self.num_columns,
+            stride: if self.row_or_column == "row" { self.num_columns } else { self.num_rows },
+            column_map: None,
+            column_names: self.column_names,
+            tags: self.tags,
+            index,
+            row_or_column: self.row_or_column,
+        })
+    }
+}
@@ -1,104 +1,257 @@
-use std::error::Error;
 use std::time::Instant;
-use crate::dataframe::DataFrame;
-use crate::operations::dlog::{self, dlog_block};
-use std::sync::{Arc, Mutex};
+use crate::error::{Error, Result};
+use crate::align::Join;
+use crate::dataframe::{DataFrame, DataFrameBuilder};
+use crate::expr::{Expr, Value};
+use crate::index::Index;
+use crate::registry::{describe, signature, ArgKind, ArgSpec, Args, Operation, Registry};
+use crate::parser::{parse_pipeline, parse_script, Arg, Pipeline};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
-use std::sync::mpsc;
-use memmap2::MmapMut;
-use tempfile::tempfile;
+
+/// Verbs handled by the engine itself because they manage its variables
+pub const ENGINE_COMMANDS: &[&str] = &["->", "get", "vars", "drop", "eval"];
+
+/// Usage line and description of each engine verb, in the same order as `ENGINE_COMMANDS`
+const ENGINE_HELP: &[(&str, &str)] = &[
+    ("-> <name:variable>", "Bind the current frame to a variable and pass it through"),
+    ("get <name:variable>", "Replace the current frame with a bound variable"),
+    ("vars", "List bound variables with their shapes"),
+    ("drop <name:variable>...", "Remove bound variables"),
+    (
+        "eval <expression:text>... [join:text=inner]",
+        "Evaluate arithmetic over variables and numbers, e.g. \"(px / shift(px, 1)) - 1\", aligning dates with the join; `_` is the current frame",
+    ),
+];
+
+/// Worker threads for column-wise operations and CSV parsing; 0 picks one per core
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set the number of worker threads, as with `--threads`; 0 restores the default of one
+/// per available core
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// The number of worker threads to use, at least 1
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, usize::from),
+        threads => threads,
+    }
+}
 
 pub struct Engine {
+    vars: HashMap<String, DataFrame>,
+    registry: Registry,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Engine {
     pub fn new() -> Self {
+        Self::with_registry(Registry::with_builtins())
+    }
+
+    /// Create an engine that dispatches through a custom set of operations
+    pub fn with_registry(registry: Registry) -> Self {
         Engine {
+            vars: HashMap::new(),
+            registry,
         }
     }
 
-    pub fn execute_command(&mut self, command: &str, args: &[String], df: Option<DataFrame>) -> Result<DataFrame, Box<dyn Error>> {
+    /// Add an operation, replacing any built-in of the same name
+    pub fn register<O: Operation + 'static>(&mut self, op: O) {
+        self.registry.register(op);
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Every command name the engine understands, in sorted order
+    pub fn command_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = ENGINE_COMMANDS
+            .iter()
+            .copied()
+            .chain(self.registry.names())
+            .map(String::from)
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Overview of every command, or detailed usage of one when `command` is given
+    pub fn help(&self, command: Option<&str>) -> Result<String> {
         match command {
-            "load" => {
-                let file_path = args.get(0).ok_or("File path missing for load")?;
-                let new_df = DataFrame::new_from_csv(file_path, "row")?;
-                return Ok(new_df);
+            Some(name) => {
+                if let Some(op) = self.registry.get(name) {
+                    return Ok(describe(op));
+                }
+                let index = ENGINE_COMMANDS
+                    .iter()
+                    .position(|&verb| verb == name)
+                    .ok_or_else(|| Error::UnknownCommand(name.to_string()))?;
+                let (usage, help) = ENGINE_HELP[index];
+                Ok(format!("Usage: {}\n\n  {}\n", usage, help))
             }
-            // Pass the DataFrame to the dlog operation
-            "dlog" => {
-                let input_df = df.ok_or("No current DataFrame to process for dlog")?;
-                let df = dlog::dlog(&input_df)?;
-                return Ok(df)
+            None => {
+                let mut entries: Vec<(String, &str)> = ENGINE_HELP
+                    .iter()
+                    .map(|&(usage, help)| (usage.to_string(), help))
+                    .chain(self.registry.iter().map(|op| (signature(op), op.help())))
+                    .collect();
+                entries.sort();
+                let width = entries.iter().map(|(usage, _)| usage.len()).max().unwrap_or(0);
+                let mut text = String::from("Commands:\n");
+                for (usage, help) in entries {
+                    text.push_str(&format!("  {:<width$}  {}\n", usage, help, width = width));
+                }
+                text.push_str("\nRun `help <command>` for details on one command.\n");
+                Ok(text)
             }
+        }
+    }
 
-            "dlog_multithread" => {
-                let input_df = df.ok_or("No current DataFrame to process for dlog")?;
-                let df = Engine::parallel_process(&input_df, Arc::new(dlog_block), 4)?;
-                return Ok(df)
+    pub fn execute_command(&mut self, command: &str, args: &[String], df: Option<DataFrame>) -> Result<DataFrame> {
+        match command {
+            "->" => {
+                let input_df = no_frame(df, command)?;
+                let name = args.first().ok_or_else(|| engine_usage(command, "missing argument <name:variable>"))?;
+                self.vars.insert(name.clone(), input_df.clone());
+                Ok(input_df)
+            }
+            "get" => {
+                let name = args.first().ok_or_else(|| engine_usage(command, "missing argument <name:variable>"))?;
+                Ok(self.var(name)?.clone())
+            }
+            "vars" => {
+                let input_df = no_frame(df, command)?;
+                for name in self.var_names() {
+                    let var = &self.vars[name];
+                    println!("{}\t{} rows x {} columns", name, var.num_rows, var.num_columns);
+                }
+                Ok(input_df)
             }
+            "drop" => {
+                let input_df = no_frame(df, command)?;
+                if args.is_empty() {
+                    return Err(engine_usage(command, "missing argument <name:variable>"));
+                }
+                for name in args {
+                    self.vars.remove(name).ok_or_else(|| Error::UnknownVariable(name.clone()))?;
+                }
+                Ok(input_df)
+            }
+            "eval" => {
+                let (join, words) = match args.split_last() {
+                    Some((last, words)) if last.starts_with("join=") => (last["join=".len()..].parse()?, words),
+                    _ => (Join::default(), args),
+                };
+                let source = words.join(" ");
+                if source.trim().is_empty() {
+                    return Err(engine_usage(command, "missing argument <expression:text>"));
+                }
+                let lookup = |name: &str| match name {
+                    "_" => df.clone().ok_or_else(|| Error::NoInput("No current DataFrame for _ in eval".to_string())),
+                    _ => self.var(name).cloned(),
+                };
+                match Expr::parse(&source)?.eval(&lookup, join)? {
+                    Value::Frame(result) => Ok(result),
+                    Value::Scalar(_) => Err(Error::InvalidValue(format!("'{}' refers to no frame", source))),
+                }
+            }
+            _ => {
+                let op = self
+                    .registry
+                    .get(command)
+                    .ok_or_else(|| Error::UnknownCommand(command.to_string()))?;
+                let (frame_names, op_args) = bind_args(op, args)?;
 
-            "print" => {
-                let input_df = df.ok_or("No current DataFrame to process for dlog")?;
-                input_df.print();
-                return Ok(input_df)
+                // Named frames come first; the current frame fills in when fewer were given
+                let mut inputs = Vec::with_capacity(op.arity());
+                if frame_names.len() < op.arity() {
+                    inputs.push(no_frame(df, command)?);
+                }
+                for name in frame_names {
+                    inputs.push(self.var(&name)?.clone());
+                }
+                op.apply(&inputs, &op_args)
             }
-            //"after" => self.current_df = Some(after::main(self.current_df.as_ref().ok_or("No current DataFrame")?, &args[0])?),
-            //"before" => self.current_df = Some(before::main(self.current_df.as_ref().ok_or("No current DataFrame")?, &args[0])?),
-            //"cgrep" => self.current_df = Some(cgrep::main(self.current_df.as_ref().ok_or("No current DataFrame")?, args)?),
-            //"signal" => self.current_df = Some(signal::main(self.current_df.as_ref().ok_or("No current DataFrame")?)?),
-            //"dlog" => {self.current_df = Some(dlog(df)?);},
-            // "unitscale" => {
-            //     let window_size: usize = args[0].parse()?;
-            //     let target_vol: f64 = args[1].parse()?;
-            //     self.current_df = Some(vol_scale::main(self.current_df.as_ref().ok_or("No current DataFrame")?, window_size, target_vol)?);
-            // },
-            // "mult" => {
-            //     let df2 = self.vars.get(&args[0]).ok_or("Variable not found")?;
-            //     self.current_df = Some(multiply::main(self.current_df.as_ref().ok_or("No current DataFrame")?, df2)?);
-            // },
-            //"load" => self.current_df = Some(self.load_csv(&args[0])?),
-            // "save" => {
-            //     save::main(self.current_df.as_ref().ok_or("No current DataFrame")?, &args[0])?;
-            // },
-            // "->" => {
-            //     self.vars.insert(args[0].clone(), self.current_df.clone().ok_or("No current DataFrame")?);
-            // },
-            // "ffill" => self.current_df = Some(ffill::main(self.current_df.as_ref().ok_or("No current DataFrame")?)),
-            // "ewa" => self.current_df = Some(ewa::main(self.current_df.as_ref().ok_or("No current DataFrame")?)?),
-            // "cumsum" => {
-            //     let start_number: f64 = args[0].parse()?;
-            //     self.current_df = Some(cumsum::main(self.current_df.as_mut().ok_or("No current DataFrame")?, start_number)?);
-            // },
-            // "shift" => {
-            //     let period: i32 = args[0].parse()?;
-            //     self.current_df = Some(shift::main(self.current_df.as_ref().ok_or("No current DataFrame")?, period)?);
-            // },
-            // "plot" => {
-            //     plot::main(self.current_df.as_ref().ok_or("No current DataFrame")?)?;
-            // },
-            // "momentum" => {
-            //     let lookback: usize = args[0].parse()?;
-            //     let frequency: usize = args[1].parse()?;
-            //     self.current_df = Some(momentum::main(self.current_df.as_ref().ok_or("No current DataFrame")?, lookback, frequency)?);
-            // },
-            _ => return Err(format!("Unknown command: {}", command).into()),
         }
     }
 
-    pub fn process_commands(&mut self, command_string: &str) -> Result<(), Box<dyn Error>> {
+    /// Look up a bound variable by name
+    pub fn var(&self, name: &str) -> Result<&DataFrame> {
+        self.vars.get(name).ok_or_else(|| Error::UnknownVariable(name.to_string()))
+    }
+
+    /// Bind a DataFrame to a variable name, replacing any previous binding
+    pub fn set_var(&mut self, name: &str, df: DataFrame) {
+        self.vars.insert(name.to_string(), df);
+    }
+
+    /// Names of all bound variables in sorted order
+    pub fn var_names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.vars.keys().collect();
+        names.sort();
+        names
+    }
+
+    /// Run every stage of a parsed pipeline, tagging failures with the stage that raised them
+    pub fn execute_pipeline(&mut self, pipeline: &Pipeline, df: Option<DataFrame>) -> Result<Option<DataFrame>> {
+        let mut current_df = df;
+        for command in &pipeline.commands {
+            let args: Vec<String> = command.args.iter().map(Arg::text).collect();
+            let result = self.execute_command(&command.name, &args, current_df).map_err(|err| Error::Stage {
+                line: command.span.line,
+                column: command.span.column,
+                stage: command.stage,
+                command: command.to_string(),
+                source: Box::new(err),
+            })?;
+            current_df = Some(result);
+        }
+        Ok(current_df)
+    }
+
+    pub fn process_commands(&mut self, command_string: &str) -> Result<()> {
+        // Parse the whole pipeline before touching any data
+        let pipeline = parse_pipeline(command_string)?;
+        self.run(&[pipeline])
+    }
+
+    /// Run a script holding one pipeline per line; the current frame and variables
+    /// carry over from one line to the next
+    pub fn process_script(&mut self, source: &str) -> Result<()> {
+        let pipelines = parse_script(source)?;
+        if pipelines.is_empty() {
+            return Err(Error::NoInput("Script contains no commands".to_string()));
+        }
+        self.run(&pipelines)
+    }
+
+    fn run(&mut self, pipelines: &[Pipeline]) -> Result<()> {
         // Measure execution time
         let start = Instant::now();
 
-        // Step 1: Load initial DataFrame from stdin
-        let mut current_df = self.load_from_stdin()?;
+        // Step 1: Load initial DataFrame from stdin, unless the first pipeline starts with
+        // an operation that takes no input frame, such as `load`
+        let first = &pipelines[0].commands[0].name;
+        let starts_with_load = self.registry.get(first).is_some_and(|op| op.arity() == 0);
+        let mut current_df = if starts_with_load { None } else { Some(self.load_from_stdin()?) };
 
         // Step 2: Process the commands
-        for cmd in command_string.split('|') {
-            let parts: Vec<String> = cmd.trim().split_whitespace().map(String::from).collect();
-            if let Some((command, args)) = parts.split_first() {
-                current_df = self.execute_command(command, args, Some(current_df))?;
-            }
+        for pipeline in pipelines {
+            current_df = self.execute_pipeline(pipeline, current_df)?;
         }
+        let current_df = current_df.ok_or_else(|| Error::NoInput("No current DataFrame to output".to_string()))?;
 
         let duration = start.elapsed();
     
@@ -114,87 +267,126 @@ impl Engine {
         Ok(())
     }
     
-    fn load_from_stdin(&self) -> Result<DataFrame, Box<dyn Error>> {
+    fn load_from_stdin(&self) -> Result<DataFrame> {
         DataFrame::from_stdin("column")
     }
 
    
-    pub fn parallel_process<F>(
-        input_df: &DataFrame,
-        operation: Arc<F>,
-        max_threads: usize,
-    ) -> Result<DataFrame, Box<dyn Error>>
+    /// Run `operation` over each column on worker threads. The operation receives one
+    /// input column and the matching output column, both from top to bottom.
+    pub fn parallel_process<F>(input_df: &DataFrame, operation: F) -> Result<DataFrame>
     where
-        F: Fn(&[u8], &mut [u8], &[usize]) + Send + Sync + 'static,
+        F: Fn(&[f64], &mut [f64]) + Sync,
     {
-        let num_blocks = input_df.offsets.len();
-    
-        // Create an output memory map
-        let output_size = input_df.num_rows * input_df.num_columns * 8;
-        let tmpfile = tempfile()?;
-        tmpfile.set_len(output_size as u64)?;
-        let output_mmap = unsafe { MmapMut::map_mut(&tmpfile)? };
-        let output_mmap = Arc::new(Mutex::new(output_mmap));
-    
-        // Share input memory map
-        let input_mmap = Arc::new(input_df.mmap.clone());
-    
-        // Create a channel to distribute work
-        let (tx, rx) = mpsc::channel();
-    
-        // Send all blocks of offsets to the channel
-        for block_offsets in &input_df.offsets {
-            tx.send(block_offsets.clone()).unwrap();
-        }
-        drop(tx); // Close the sender to signal no more work
-    
-        // Wrap the receiver in Arc<Mutex> to share among threads
-        let rx = Arc::new(Mutex::new(rx));
-    
-        // Worker pool
-        let mut handles = vec![];
-        for _ in 0..max_threads.min(num_blocks) {
-            let input_mmap = Arc::clone(&input_mmap);
-            let output_mmap = Arc::clone(&output_mmap);
-            let rx = Arc::clone(&rx);
-            let operation = Arc::clone(&operation);
-    
-            let handle = thread::spawn(move || {
-                while let Ok(block_offsets) = rx.lock().unwrap().recv() {
-                    let mut output_lock = output_mmap.lock().unwrap();
-    
-                    // Pass the block of offsets to the operation
-                    operation(
-                        &input_mmap[..],                  // Entire input mmap
-                        &mut output_lock[..],             // Entire output mmap
-                        &block_offsets[..],               // Current block of offsets
-                    );
+        Self::parallel_process_into(input_df, input_df.index.clone(), operation)
+    }
+
+    /// Like `parallel_process`, for operations whose output has different rows than the
+    /// input; output columns have one value per row of `index`
+    pub fn parallel_process_into<F>(input_df: &DataFrame, index: Index, operation: F) -> Result<DataFrame>
+    where
+        F: Fn(&[f64], &mut [f64]) + Sync,
+    {
+        let (num_rows, num_columns) = (index.len(), input_df.num_columns);
+        // Workers own whole output columns, so they are written in column layout
+        let mut output = DataFrameBuilder::new(index, input_df.column_names.clone(), "column")?.tags(input_df.tags.clone());
+
+        if num_rows > 0 && num_columns > 0 {
+            // Each worker gets a disjoint run of adjacent columns; no locks are needed
+            let columns_per_worker = num_columns.div_ceil(threads().min(num_columns));
+            let process = |first_column: usize, block: &mut [f64]| {
+                for (offset, column) in block.chunks_mut(num_rows).enumerate() {
+                    operation(&input_df.column(first_column + offset), column);
                 }
-            });
-    
-            handles.push(handle);
+            };
+            let blocks = output.values_mut().chunks_mut(columns_per_worker * num_rows);
+            thread::scope(|scope| {
+                let workers: Vec<_> = blocks
+                    .enumerate()
+                    .map(|(worker, block)| scope.spawn(move || process(worker * columns_per_worker, block)))
+                    .collect();
+                workers
+                    .into_iter()
+                    .try_for_each(|worker| worker.join().map_err(|_| Error::Thread("A worker thread panicked".to_string())))
+            })?;
         }
-    
-        // Wait for all threads to finish
-        for handle in handles {
-            handle.join().unwrap();
+
+        let output = output.build()?;
+        if input_df.row_or_column == "column" {
+            return Ok(output);
         }
-    
-        // Unwrap the output memory map
-        let output_mmap = Arc::try_unwrap(output_mmap)
-            .expect("Failed to unwrap Arc")
-            .into_inner()
-            .expect("Failed to lock Mutex");
-    
-        Ok(DataFrame {
-            mmap: Arc::new(output_mmap.make_read_only()?),
-            num_rows: input_df.num_rows,
-            num_columns: input_df.num_columns,
-            column_names: input_df.column_names.clone(),
-            row_names: input_df.row_names.clone(),
-            row_or_column: input_df.row_or_column.clone(),
-            offsets: input_df.offsets.clone(),
-        })
+        let mut rows = DataFrameBuilder::new(output.index.clone(), output.column_names.clone(), "row")?.tags(output.tags.clone());
+        for col in 0..num_columns {
+            rows.set_column(col, &output.column(col));
+        }
+        rows.build()
     }
-    
-}
\ No newline at end of file
+}
+
+/// Match positional arguments against an operation's schema, splitting out the names of
+/// frame arguments and filling in defaults for the rest. Errors quote the expected usage.
+fn bind_args(op: &dyn Operation, args: &[String]) -> Result<(Vec<String>, Args)> {
+    let mut frame_names = Vec::new();
+    let mut values = Vec::new();
+
+    // `name=value` gives a non-variadic argument by name, leaving the others positional
+    let by_name = |arg: &str, spec: &ArgSpec| -> Option<String> {
+        let (name, value) = arg.split_once('=')?;
+        (name == spec.name && !spec.variadic).then(|| value.to_string())
+    };
+    let (named, positional): (Vec<&String>, Vec<&String>) =
+        args.iter().partition(|arg| op.args().iter().any(|spec| by_name(arg, spec).is_some()));
+    let mut remaining = positional.into_iter().peekable();
+    // A join mode such as `outer` where an optional frame could go is the `join` argument
+    let takes_join = op.args().iter().any(|spec| spec.name == "join");
+    let is_join = |arg: &&String| takes_join && arg.parse::<Join>().is_ok();
+
+    for spec in op.args() {
+        let taken: Vec<String> = if let Some(value) = named.iter().rev().find_map(|arg| by_name(arg, spec)) {
+            vec![value]
+        } else if spec.kind == ArgKind::Frame && spec.optional && remaining.peek().is_some_and(is_join) {
+            Vec::new()
+        } else if spec.variadic {
+            remaining.by_ref().cloned().collect()
+        } else {
+            remaining.next().cloned().into_iter().collect()
+        };
+
+        if taken.is_empty() {
+            match spec.default {
+                Some(default) => values.push(default.to_string()),
+                None if spec.optional => {}
+                None => return Err(usage_error(op, format!("missing argument {}", spec))),
+            }
+            continue;
+        }
+        for value in &taken {
+            spec.validate(value).map_err(|err| usage_error(op, err))?;
+        }
+        if spec.kind == ArgKind::Frame {
+            frame_names.extend(taken);
+        } else {
+            values.extend(taken);
+        }
+    }
+
+    if remaining.next().is_some() {
+        return Err(usage_error(op, format!("too many arguments, expected {}", op.args().len())));
+    }
+    Ok((frame_names, Args::new(values)))
+}
+
+fn usage_error(op: &dyn Operation, message: impl ToString) -> Error {
+    Error::Usage { command: op.name().to_string(), message: message.to_string(), usage: signature(op) }
+}
+
+/// Usage error for one of the engine's own verbs
+fn engine_usage(command: &str, message: &str) -> Error {
+    let index = ENGINE_COMMANDS.iter().position(|&verb| verb == command).unwrap_or(0);
+    Error::Usage { command: command.to_string(), message: message.to_string(), usage: ENGINE_HELP[index].0.to_string() }
+}
+
+/// The current frame, or an error naming the command that needed it
+fn no_frame(df: Option<DataFrame>, command: &str) -> Result<DataFrame> {
+    df.ok_or_else(|| Error::NoInput(format!("No current DataFrame to process for {}", command)))
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io;
+
+use crate::parser::ParseError;
+
+/// Everything that can go wrong while loading data or running a pipeline
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),                          // Malformed pipeline or script
+    Csv { line: usize, message: String },       // Malformed input data (1-based line)
+    Format(String),                             // Corrupt or unsupported binary, Parquet or Arrow file
+    Index(String),                              // Dates out of order or repeated
+    Shape(String),                              // Frames or values of incompatible dimensions
+    MissingColumn(String),
+    UnknownVariable(String),
+    UnknownCommand(String),
+    Usage { command: String, message: String, usage: String },  // Arguments don't match the signature
+    InvalidValue(String),                       // An argument or cell that can't be interpreted
+    NoInput(String),                            // No frame, no stdin data or an empty script
+    Io(io::Error),
+    Thread(String),                             // A worker thread panicked
+    Stage {                                     // Failure of one pipeline stage, with its position
+        line: usize,
+        column: usize,
+        stage: usize,
+        command: String,
+        source: Box<Error>,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// The underlying error, looking through pipeline stage context
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Stage { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Process exit code for this kind of error; 1 is left for general usage errors
+    pub fn exit_code(&self) -> i32 {
+        match self.root() {
+            Error::Parse(_) => 2,
+            Error::UnknownCommand(_) => 3,
+            Error::Usage { .. } => 4,
+            Error::UnknownVariable(_) => 5,
+            Error::MissingColumn(_) => 6,
+            Error::Shape(_) => 7,
+            Error::Csv { .. } => 8,
+            Error::InvalidValue(_) => 9,
+            Error::NoInput(_) => 10,
+            Error::Io(_) => 11,
+            Error::Thread(_) => 12,
+            Error::Format(_) => 13,
+            Error::Index(_) => 14,
+            Error::Stage { .. } => unreachable!("root() looks through stages"),
+        }
+    }
+
+    /// Like `Display`, but parse errors also show the source line with a caret
+    pub fn render(&self, source: &str) -> String {
+        match self {
+            Error::Parse(err) => err.render(source),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Csv { line, message } => write!(f, "line {}: {}", line, message),
+            Error::Format(message) => write!(f, "{}", message),
+            Error::Index(message) => write!(f, "{}", message),
+            Error::Shape(message) => write!(f, "{}", message),
+            Error::MissingColumn(name) => write!(f, "Column not found: {}", name),
+            Error::UnknownVariable(name) => write!(f, "Variable not found: {}", name),
+            Error::UnknownCommand(name) => write!(f, "Unknown command: {} (run `help` to list commands)", name),
+            Error::Usage { command, message, usage } => write!(f, "{}: {}\n  usage: {}", command, message, usage),
+            Error::InvalidValue(message) => write!(f, "{}", message),
+            Error::NoInput(message) => write!(f, "{}", message),
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Thread(message) => write!(f, "{}", message),
+            Error::Stage { line, column, stage, command, source } => {
+                write!(f, "line {}, column {} (stage {}, `{}`): {}", line, column, stage, command, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Stage { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        let line = err.position().map_or(0, |pos| pos.line() as usize);
+        let message = err.to_string();
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => Error::Io(err),
+            _ => Error::Csv { line, message },
+        }
+    }
+}
+
+impl From<arrow_schema::ArrowError> for Error {
+    fn from(err: arrow_schema::ArrowError) -> Self {
+        match err {
+            arrow_schema::ArrowError::IoError(_, err) => Error::Io(err),
+            other => Error::Format(other.to_string()),
+        }
+    }
+}
+
+impl From<parquet::errors::ParquetError> for Error {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        Error::Format(err.to_string())
+    }
+}
+
+impl From<rustyline::error::ReadlineError> for Error {
+    fn from(err: rustyline::error::ReadlineError) -> Self {
+        match err {
+            rustyline::error::ReadlineError::Io(err) => Error::Io(err),
+            other => Error::Io(io::Error::other(other)),
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt;
+
+use crate::align::Join;
+use crate::error::{Error, Result};
+use crate::operations::{binary, shift};
+use crate::DataFrame;
+
+// Arithmetic over frames and numbers, as used by `eval`:
+//
+//     (px / shift(px, 1)) - 1
+//     sig * 0.1 / vol
+//     clip(abs(ret) > 0.05, 0, 1)
+//
+// From loosest to tightest binding: comparisons (< <= > >= == !=, giving 1 or 0, NaN if
+// either side is NaN), + and -, * and /, unary minus, then ^ (right associative, so
+// -x^2 is -(x^2)). Names refer to bound variables, with `_` for the current frame.
+// Frames combine through the binary operations, so their dates are aligned and their
+// columns matched by name.
+
+/// A parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl BinOp {
+    fn function(self) -> fn(f64, f64) -> f64 {
+        fn compare(a: f64, b: f64, holds: bool) -> f64 {
+            if a.is_nan() || b.is_nan() {
+                f64::NAN
+            } else {
+                f64::from(u8::from(holds))
+            }
+        }
+        match self {
+            BinOp::Add => |a, b| a + b,
+            BinOp::Sub => |a, b| a - b,
+            BinOp::Mul => |a, b| a * b,
+            BinOp::Div => |a, b| a / b,
+            BinOp::Pow => f64::powf,
+            BinOp::Lt => |a, b| compare(a, b, a < b),
+            BinOp::Le => |a, b| compare(a, b, a <= b),
+            BinOp::Gt => |a, b| compare(a, b, a > b),
+            BinOp::Ge => |a, b| compare(a, b, a >= b),
+            BinOp::Eq => |a, b| compare(a, b, a == b),
+            BinOp::Ne => |a, b| compare(a, b, a != b),
+        }
+    }
+}
+
+/// Built-in functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Abs,
+    Log,                                // Natural logarithm
+    Exp,
+    Sqrt,
+    Sign,                               // -1, 0 or 1
+    Clip,                               // clip(x, low, high)
+    Shift,                              // shift(x, periods), one period by default
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Func> {
+        Some(match name {
+            "abs" => Func::Abs,
+            "log" => Func::Log,
+            "exp" => Func::Exp,
+            "sqrt" => Func::Sqrt,
+            "sign" => Func::Sign,
+            "clip" => Func::Clip,
+            "shift" => Func::Shift,
+            _ => return None,
+        })
+    }
+
+    /// Smallest and largest number of arguments
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Func::Clip => (3, 3),
+            Func::Shift => (1, 2),
+            _ => (1, 1),
+        }
+    }
+}
+
+/// The result of evaluating an expression
+#[derive(Clone)]
+pub enum Value {
+    Scalar(f64),
+    Frame(DataFrame),
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Scalar(x) => write!(f, "Scalar({})", x),
+            Value::Frame(df) => write!(f, "Frame({} rows x {} columns)", df.num_rows, df.num_columns),
+        }
+    }
+}
+
+impl Expr {
+    pub fn parse(source: &str) -> Result<Expr> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, end: source.chars().count() + 1 };
+        let expr = parser.comparison()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((token, column)) => Err(invalid(*column, format!("unexpected {}", token))),
+        }
+    }
+
+    /// Evaluate with variables from `lookup`, aligning frames with `join`
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Result<DataFrame>, join: Join) -> Result<Value> {
+        match self {
+            Expr::Number(x) => Ok(Value::Scalar(*x)),
+            Expr::Var(name) => Ok(Value::Frame(lookup(name)?)),
+            Expr::Neg(inner) => unary(inner.eval(lookup, join)?, |x| -x),
+            Expr::Binary(op, left, right) => {
+                let f = op.function();
+                match (left.eval(lookup, join)?, right.eval(lookup, join)?) {
+                    (Value::Scalar(a), Value::Scalar(b)) => Ok(Value::Scalar(f(a, b))),
+                    (Value::Frame(a), Value::Scalar(b)) => Ok(Value::Frame(a.map(|x| f(x, b))?)),
+                    (Value::Scalar(a), Value::Frame(b)) => Ok(Value::Frame(b.map(|x| f(a, x))?)),
+                    (Value::Frame(a), Value::Frame(b)) => Ok(Value::Frame(binary::apply_with(&a, &b, join, f)?)),
+                }
+            }
+            Expr::Call(func, args) => {
+                let x = args[0].eval(lookup, join)?;
+                let scalar = |i: usize, name: &str| match args.get(i).map(|arg| arg.eval(lookup, join)).transpose()? {
+                    Some(Value::Scalar(value)) => Ok(Some(value)),
+                    Some(Value::Frame(_)) => Err(Error::InvalidValue(format!("The {} of {:?} must be a number", name, func))),
+                    None => Ok(None),
+                };
+                match func {
+                    Func::Abs => unary(x, f64::abs),
+                    Func::Log => unary(x, f64::ln),
+                    Func::Exp => unary(x, f64::exp),
+                    Func::Sqrt => unary(x, f64::sqrt),
+                    Func::Sign => unary(x, |x| if x == 0.0 { 0.0 } else { x.signum() }),
+                    Func::Clip => {
+                        let (low, high) = (scalar(1, "lower bound")?.unwrap_or(f64::NAN), scalar(2, "upper bound")?.unwrap_or(f64::NAN));
+                        if low.is_nan() || high.is_nan() || low > high {
+                            return Err(Error::InvalidValue(format!("clip needs low <= high, got {} and {}", low, high)));
+                        }
+                        unary(x, move |x| x.clamp(low, high))
+                    }
+                    Func::Shift => {
+                        let periods = scalar(1, "period count")?.unwrap_or(1.0);
+                        if periods.fract() != 0.0 || periods.abs() > f64::from(i32::MAX) {
+                            return Err(Error::InvalidValue(format!("shift needs a whole number of periods, got {}", periods)));
+                        }
+                        match x {
+                            Value::Frame(df) => Ok(Value::Frame(shift::shift(&df, periods as i32)?)),
+                            Value::Scalar(x) => Ok(Value::Scalar(x)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn unary(value: Value, f: impl Fn(f64) -> f64) -> Result<Value> {
+    match value {
+        Value::Scalar(x) => Ok(Value::Scalar(f(x))),
+        Value::Frame(df) => Ok(Value::Frame(df.map(f)?)),
+    }
+}
+
+fn invalid(column: usize, message: impl fmt::Display) -> Error {
+    Error::InvalidValue(format!("Invalid expression at column {}: {}", column, message))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(x) => write!(f, "number {}", x),
+            Token::Name(name) => write!(f, "name '{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+/// Operators, longest first so `<=` isn't read as `<`
+const OPERATORS: &[&str] = &["<=", ">=", "==", "!=", "+", "-", "*", "/", "^", "<", ">"];
+
+/// Split an expression into tokens with their 1-based columns
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        let column = source[..source.len() - rest.len()].chars().count() + 1;
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let (token, len) = if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let len = number_len(rest);
+            let number = rest[..len].parse().map_err(|_| invalid(column, format!("bad number '{}'", &rest[..len])))?;
+            (Token::Number(number), len)
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            // `$name` is accepted for consistency with pipeline arguments
+            let start = usize::from(c == '$');
+            let len = start + rest[start..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len() - start);
+            if len == start {
+                return Err(invalid(column, "expected a name after '$'"));
+            }
+            (Token::Name(rest[start..len].to_string()), len)
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            (Token::Op(op), op.len())
+        } else {
+            match c {
+                '(' => (Token::Open, 1),
+                ')' => (Token::Close, 1),
+                ',' => (Token::Comma, 1),
+                _ => return Err(invalid(column, format!("unexpected character '{}'", c))),
+            }
+        };
+        tokens.push((token, column));
+        rest = &rest[len..];
+    }
+    Ok(tokens)
+}
+
+/// Length of the number at the start of `s`: digits, a fraction and an exponent
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |from: usize| from + bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let mut len = digits(0);
+    if bytes.get(len) == Some(&b'.') {
+        len = digits(len + 1);
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let end = digits(len + 1 + sign);
+        if end > len + 1 + sign {
+            len = end;
+        }
+    }
+    len
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,                         // Column just past the input, for errors at its end
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume the next token if it is one of the given operators
+    fn operator(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some((Token::Op(op), _)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.peek() {
+            Some((token, _)) if *token == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some((token, column)) => Err(invalid(*column, format!("expected {}, found {}", expected, token))),
+            None => Err(invalid(self.end, format!("expected {}", expected))),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.additive()?;
+        let op = match self.operator(&["<", "<=", ">", ">=", "==", "!="]) {
+            Some("<") => BinOp::Lt,
+            Some("<=") => BinOp::Le,
+            Some(">") => BinOp::Gt,
+            Some(">=") => BinOp::Ge,
+            Some("==") => BinOp::Eq,
+            Some(_) => BinOp::Ne,
+            None => return Ok(left),
+        };
+        Ok(Expr::Binary(op, Box::new(left), Box::new(self.additive()?)))
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        let mut expr = self.multiplicative()?;
+        while let Some(op) = self.operator(&["+", "-"]) {
+            let op = if op == "+" { BinOp::Add } else { BinOp::Sub };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.multiplicative()?));
+        }
+        Ok(expr)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while let Some(op) = self.operator(&["*", "/"]) {
+            let op = if op == "*" { BinOp::Mul } else { BinOp::Div };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.operator(&["-"]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.operator(&["+"]).is_some() {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr> {
+        let base = self.primary()?;
+        if self.operator(&["^"]).is_some() {
+            return Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let Some((token, column)) = self.tokens.get(self.pos).cloned() else {
+            return Err(invalid(self.end, "unexpected end of expression"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Number(x) => Ok(Expr::Number(x)),
+            Token::Open => {
+                let expr = self.comparison()?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Token::Name(name) if self.peek().is_some_and(|(token, _)| *token == Token::Open) => {
+                let func = Func::from_name(&name).ok_or_else(|| invalid(column, format!("unknown function '{}'", name)))?;
+                self.pos += 1;
+                let mut args = vec![self.comparison()?];
+                while self.peek().is_some_and(|(token, _)| *token == Token::Comma) {
+                    self.pos += 1;
+                    args.push(self.comparison()?);
+                }
+                self.expect(Token::Close)?;
+                let (min, max) = func.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(invalid(column, format!("{} takes {} arguments, got {}", name, if min == max { min.to_string() } else { format!("{} to {}", min, max) }, args.len())));
+                }
+                Ok(Expr::Call(func, args))
+            }
+            Token::Name(name) => Ok(Expr::Var(name)),
+            token => Err(invalid(column, format!("unexpected {}", token))),
+        }
+    }
+}
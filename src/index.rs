@@ -0,0 +1,231 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::error::{Error, Result};
+
+/// Row index of a frame: calendar dates, intraday timestamps or, for anything that doesn't
+/// parse as either, plain labels
+#[derive(Clone, Debug, PartialEq)]
+pub enum Index {
+    Dates(Vec<NaiveDate>),
+    Timestamps(Vec<NaiveDateTime>, Option<FixedOffset>),    // Wall-clock times, all in one UTC offset if known
+    Labels(Vec<String>),
+}
+
+/// How to turn row labels into an index
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum IndexFormat {
+    #[default]
+    Auto,                               // %Y-%m-%d dates, else ISO 8601 timestamps, else labels
+    Labels,                             // Keep the labels as they are
+    Custom(String),                     // A chrono format; timestamps if it has a time of day
+}
+
+impl FromStr for IndexFormat {
+    type Err = std::convert::Infallible;
+
+    /// `auto`, `labels` or a chrono format such as `%d/%m/%Y`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "auto" => IndexFormat::Auto,
+            "labels" => IndexFormat::Labels,
+            format => IndexFormat::Custom(format.to_string()),
+        })
+    }
+}
+
+/// Naive timestamp formats tried, after RFC 3339, when inferring an index
+const TIMESTAMP_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"];
+
+impl Index {
+    /// Parse labels with a format. `Auto` never fails: labels that aren't all dates or all
+    /// timestamps are kept as labels. A custom format must match every label.
+    pub fn parse(labels: Vec<String>, format: &IndexFormat) -> Result<Index> {
+        match format {
+            IndexFormat::Auto => Ok(Self::infer(labels)),
+            IndexFormat::Labels => Ok(Index::Labels(labels)),
+            IndexFormat::Custom(format) => {
+                let mismatch = |label: &str| {
+                    Error::InvalidValue(format!("Row label '{}' doesn't match the index format '{}'", label, format))
+                };
+                if !has_time_of_day(format) {
+                    let dates = labels.iter().map(|label| NaiveDate::parse_from_str(label, format).map_err(|_| mismatch(label)));
+                    return Ok(Index::Dates(dates.collect::<Result<_>>()?));
+                }
+                let zoned = format.contains("%z") || format.contains("%:z") || format.contains("%#z") || format.contains("%+");
+                let times = labels.iter().map(|label| {
+                    if zoned {
+                        DateTime::parse_from_str(label, format).map(|time| (time.naive_local(), Some(*time.offset())))
+                    } else {
+                        NaiveDateTime::parse_from_str(label, format).map(|time| (time, None))
+                    }
+                    .map_err(|_| mismatch(label))
+                });
+                let times = times.collect::<Result<Vec<_>>>()?;
+                timestamps(times).ok_or_else(|| Error::InvalidValue("Index mixes zoned and unzoned timestamps".to_string()))
+            }
+        }
+    }
+
+    fn infer(labels: Vec<String>) -> Index {
+        if let Ok(dates) = labels.iter().map(|label| NaiveDate::parse_from_str(label, "%Y-%m-%d")).collect() {
+            return Index::Dates(dates);
+        }
+        let times: Option<Vec<_>> = labels.iter().map(|label| parse_timestamp(label)).collect();
+        match times.and_then(timestamps) {
+            Some(index) => index,
+            None => Index::Labels(labels),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Index::Dates(dates) => dates.len(),
+            Index::Timestamps(times, _) => times.len(),
+            Index::Labels(labels) => labels.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// True for dates and timestamps
+    pub fn is_temporal(&self) -> bool {
+        !matches!(self, Index::Labels(_))
+    }
+
+    /// The label of one row: dates as %Y-%m-%d, timestamps with fractional seconds only
+    /// when present and the UTC offset when known
+    pub fn label(&self, row: usize) -> String {
+        match self {
+            Index::Dates(dates) => dates[row].format("%Y-%m-%d").to_string(),
+            Index::Timestamps(times, offset) => {
+                let time = times[row];
+                let mut label = time.format("%Y-%m-%d %H:%M:%S").to_string();
+                if time.nanosecond() != 0 {
+                    label.push_str(&time.format("%.f").to_string());
+                }
+                if let Some(offset) = offset {
+                    label.push_str(&offset.to_string());
+                }
+                label
+            }
+            Index::Labels(labels) => labels[row].clone(),
+        }
+    }
+
+    /// Every label, top to bottom
+    pub fn labels(&self) -> Vec<String> {
+        (0..self.len()).map(|row| self.label(row)).collect()
+    }
+
+    /// The point in time of one row, with dates at midnight; None for labels
+    pub fn time(&self, row: usize) -> Option<NaiveDateTime> {
+        match self {
+            Index::Dates(dates) => Some(dates[row].and_time(NaiveTime::MIN)),
+            Index::Timestamps(times, _) => Some(times[row]),
+            Index::Labels(_) => None,
+        }
+    }
+
+    /// The rows in `range`
+    pub fn slice(&self, range: Range<usize>) -> Index {
+        match self {
+            Index::Dates(dates) => Index::Dates(dates[range].to_vec()),
+            Index::Timestamps(times, offset) => Index::Timestamps(times[range].to_vec(), *offset),
+            Index::Labels(labels) => Index::Labels(labels[range].to_vec()),
+        }
+    }
+
+    /// The given rows, in the given order
+    pub fn select(&self, rows: &[usize]) -> Index {
+        match self {
+            Index::Dates(dates) => Index::Dates(rows.iter().map(|&row| dates[row]).collect()),
+            Index::Timestamps(times, offset) => Index::Timestamps(rows.iter().map(|&row| times[row]).collect(), *offset),
+            Index::Labels(labels) => Index::Labels(rows.iter().map(|&row| labels[row].clone()).collect()),
+        }
+    }
+
+    /// Binary search for the first row whose time fails `pred`, as `slice::partition_point`.
+    /// The index must be temporal and increasing.
+    pub fn partition_point(&self, pred: impl Fn(NaiveDateTime) -> bool) -> Result<usize> {
+        match self {
+            Index::Dates(dates) => Ok(dates.partition_point(|date| pred(date.and_time(NaiveTime::MIN)))),
+            Index::Timestamps(times, _) => Ok(times.partition_point(|&time| pred(time))),
+            Index::Labels(_) => Err(Error::InvalidValue("The row index holds labels, not dates".to_string())),
+        }
+    }
+
+    /// Check that dates and timestamps strictly increase, so the index can be binary searched.
+    /// Labels are not checked.
+    pub fn validate(&self) -> Result<()> {
+        for row in 1..self.len() {
+            let (Some(previous), Some(current)) = (self.time(row - 1), self.time(row)) else {
+                return Ok(());
+            };
+            if current == previous {
+                return Err(Error::Index(format!("Duplicate index entry {} at row {}", self.label(row), row + 1)));
+            }
+            if current < previous {
+                return Err(Error::Index(format!(
+                    "Index is not increasing: {} at row {} comes after {}",
+                    self.label(row), row + 1, self.label(row - 1)
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<String>> for Index {
+    /// Infer the index type, as `IndexFormat::Auto`
+    fn from(labels: Vec<String>) -> Self {
+        Self::infer(labels)
+    }
+}
+
+impl From<Vec<NaiveDate>> for Index {
+    fn from(dates: Vec<NaiveDate>) -> Self {
+        Index::Dates(dates)
+    }
+}
+
+/// Whether a chrono format includes a time of day or offset
+fn has_time_of_day(format: &str) -> bool {
+    ["%H", "%I", "%k", "%l", "%M", "%S", "%T", "%R", "%r", "%X", "%s", "%z", "%:z", "%#z", "%+"]
+        .iter()
+        .any(|spec| format.contains(spec))
+}
+
+/// An ISO 8601 timestamp, with or without an offset; a bare date is midnight
+fn parse_timestamp(label: &str) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    let zoned = DateTime::parse_from_rfc3339(label).or_else(|_| DateTime::parse_from_str(label, "%Y-%m-%d %H:%M:%S%.f%:z"));
+    if let Ok(time) = zoned {
+        return Some((time.naive_local(), Some(*time.offset())));
+    }
+    TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(label, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(label, "%Y-%m-%d").ok().map(|date| date.and_time(NaiveTime::MIN)))
+        .map(|time| (time, None))
+}
+
+/// Put parsed timestamps in one offset: their own if they share one, UTC if not. None if
+/// only some have an offset.
+fn timestamps(times: Vec<(NaiveDateTime, Option<FixedOffset>)>) -> Option<Index> {
+    let offset = times.first().and_then(|&(_, offset)| offset);
+    if times.iter().all(|&(_, other)| other == offset) {
+        return Some(Index::Timestamps(times.into_iter().map(|(time, _)| time).collect(), offset));
+    }
+    if times.iter().any(|(_, offset)| offset.is_none()) {
+        return None;
+    }
+    let utc = times
+        .into_iter()
+        .map(|(time, offset)| time - chrono::Duration::seconds(offset.map_or(0, |offset| offset.local_minus_utc()).into()))
+        .collect();
+    Some(Index::Timestamps(utc, FixedOffset::east_opt(0)))
+}
@@ -1,6 +1,21 @@
+pub mod align;
+pub mod arrowfile;
+pub mod binfile;
+pub mod csvfile;
 pub mod dataframe;
+pub mod error;
+pub mod expr;
 pub mod operations;
 pub mod engine;
+pub mod index;
+pub mod metadata;
+pub mod parser;
+pub mod repl;
+pub mod registry;
 
-pub use dataframe::DataFrame;
+pub use dataframe::{DataFrame, DataFrameBuilder};
 pub use engine::Engine;
+pub use index::{Index, IndexFormat};
+pub use metadata::Tags;
+pub use error::{Error, Result};
+pub use registry::{Operation, Registry};
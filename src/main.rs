@@ -1,30 +1,84 @@
 use std::env;
+use std::fs;
 use std::io::{self, Read};
 use std::process;
-use light_r::engine::Engine;
+use light_r::engine::{self, Engine};
+use light_r::Error;
+use light_r::repl;
 
 fn main() {
     
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
     println!("Arguments: {:?}", args); // Debug: Print all arguments
 
+    // `--threads N` may come before the command and sets the worker count for parallel ops
+    if let Some(pos) = args.iter().position(|arg| arg == "--threads") {
+        match args.get(pos + 1).map(|n| n.parse::<usize>()) {
+            Some(Ok(threads)) if threads > 0 => engine::set_threads(threads),
+            _ => {
+                let err = Error::InvalidValue("--threads expects a positive number of threads".to_string());
+                eprintln!("Error: {}", err);
+                process::exit(err.exit_code());
+            }
+        }
+        args.drain(pos..pos + 2);
+    }
+
     if args.len() < 2 {
         // Check if `stdin` has input
         let mut buffer = String::new();
         if io::stdin().read_to_string(&mut buffer).is_ok() && !buffer.trim().is_empty() {
             println!("Stdin detected. Use a command to process the input.");
-        } else {
-            eprintln!("Usage: light-r <command_string>");
-            process::exit(1);
         }
+        eprintln!("Usage: light-r [--threads N] <command_string> | light-r [--threads N] -f <script.lr> | light-r repl | light-r help [command]");
+        process::exit(1);
+    }
+
+    if args[1] == "repl" {
+        if let Err(err) = repl::run() {
+            eprintln!("Error: {}", err);
+            process::exit(err.exit_code());
+        }
+        return;
     }
 
     let mut engine = Engine::new();
-    let command_string = &args[1];
-    println!("Command String: {}", command_string); // Debug: Print command string
 
-    if let Err(err) = engine.process_commands(command_string) {
-        eprintln!("Error: {}", err);
-        process::exit(1);
+    if args[1] == "help" || args[1] == "--help" {
+        match engine.help(args.get(2).map(String::as_str)) {
+            Ok(text) => print!("{}", text),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                process::exit(err.exit_code());
+            }
+        }
+        return;
+    }
+
+    let (source, result) = if args[1] == "-f" {
+        // Run a script file with one pipeline per line
+        let Some(script_path) = args.get(2) else {
+            eprintln!("Usage: light-r -f <script.lr>");
+            process::exit(1);
+        };
+        let source = match fs::read_to_string(script_path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Error: cannot read script '{}': {}", script_path, err);
+                process::exit(Error::Io(err).exit_code());
+            }
+        };
+        let result = engine.process_script(&source);
+        (source, result)
+    } else {
+        let command_string = args[1].clone();
+        println!("Command String: {}", command_string); // Debug: Print command string
+        let result = engine.process_commands(&command_string);
+        (command_string, result)
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err.render(&source));
+        process::exit(err.exit_code());
     }
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::DataFrame;
+
+// Columns carry free-form tags such as asset_class, currency, multiplier or sector. They
+// come from a sidecar file keyed by column name, either CSV:
+//
+//     column,asset_class,currency,multiplier
+//     SP500,equity,USD,50
+//     US10,rates,USD,1000
+//
+// where empty cells leave a tag unset, or JSON:
+//
+//     {"SP500": {"asset_class": "equity", "currency": "USD", "multiplier": 50}}
+//
+// A data file's sidecar sits next to it as `<stem>.meta.csv` or `<stem>.meta.json`.
+
+/// Tags of one column, by key
+pub type Tags = BTreeMap<String, String>;
+
+/// Read tags by column name from a CSV or JSON file, told apart by extension
+pub fn read(path: &str) -> Result<BTreeMap<String, Tags>> {
+    if path.to_ascii_lowercase().ends_with(".json") {
+        read_json(path)
+    } else {
+        read_csv(path)
+    }
+}
+
+fn read_csv(path: &str) -> Result<BTreeMap<String, Tags>> {
+    let mut rdr = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(File::open(path)?);
+    let keys = rdr.headers()?.clone();
+    let mut columns = BTreeMap::new();
+    for record in rdr.records() {
+        let record = record?;
+        let tags = keys
+            .iter()
+            .zip(&record)
+            .skip(1)
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        columns.insert(record[0].to_string(), tags);
+    }
+    Ok(columns)
+}
+
+fn read_json(path: &str) -> Result<BTreeMap<String, Tags>> {
+    let invalid = |message: String| Error::Format(format!("{}: {}", path, message));
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| invalid(e.to_string()))?;
+    let object = json.as_object().ok_or_else(|| invalid("expected an object of columns".to_string()))?;
+    let mut columns = BTreeMap::new();
+    for (column, tags) in object {
+        let tags = tags.as_object().ok_or_else(|| invalid(format!("tags of {} must be an object", column)))?;
+        let mut parsed = Tags::new();
+        for (key, value) in tags {
+            let value = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => value.to_string(),
+                _ => return Err(invalid(format!("tag {} of {} must be a string, number or boolean", key, column))),
+            };
+            parsed.insert(key.clone(), value);
+        }
+        columns.insert(column.clone(), parsed);
+    }
+    Ok(columns)
+}
+
+/// The sidecar of a data file, if one exists
+pub fn sidecar(path: &str) -> Option<String> {
+    let path = Path::new(path);
+    let stem = path.file_stem()?.to_str()?;
+    ["csv", "json"]
+        .iter()
+        .map(|extension| path.with_file_name(format!("{}.meta.{}", stem, extension)))
+        .find(|sidecar| sidecar.is_file())
+        .and_then(|sidecar| sidecar.to_str().map(str::to_string))
+}
+
+/// Merge tags into the frame's columns, replacing keys the columns already have. Columns
+/// the tags don't mention keep their own; tags for columns the frame doesn't have are ignored.
+pub fn attach(df: &DataFrame, columns: &BTreeMap<String, Tags>) -> DataFrame {
+    let mut tagged = df.clone();
+    for (name, tags) in tagged.column_names.iter().zip(&mut tagged.tags) {
+        if let Some(extra) = columns.get(name) {
+            tags.extend(extra.iter().map(|(key, value)| (key.clone(), value.clone())));
+        }
+    }
+    tagged
+}
@@ -1,17 +1,40 @@
-// use chrono::NaiveDate;
-// use crate::dataframe::DataFrame;
-
-// pub fn after_date(df: &DataFrame, date: NaiveDate) -> DataFrame {
-//     let start_index = df.dates.partition_point(|&d| d <= date);
-    
-//     DataFrame {
-//         dates: df.dates[start_index..].to_vec(),
-//         data: df.data[start_index..].to_vec(),
-//         column_names: df.column_names.clone()
-//     }
-// }
-
-// pub fn main(df: &DataFrame, date: &str) -> Result<DataFrame, Box<dyn std::error::Error>> {
-//     let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")?;
-//     Ok(after_date(df, date))
-// }
+use chrono::NaiveDate;
+use crate::error::{Error, Result};
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub fn after_date(df: &DataFrame, date: NaiveDate) -> Result<DataFrame> {
+    let start_index = df.index.partition_point(|time| time.date() <= date)?;
+    Ok(df.slice_rows(start_index..df.num_rows))
+}
+
+pub fn main(df: &DataFrame, date: &str) -> Result<DataFrame> {
+    let date = parse_date(date)?;
+    after_date(df, date)
+}
+
+/// Parse a `YYYY-MM-DD` date
+pub(crate) fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| Error::InvalidValue(format!("Invalid date '{}': {}", date, e)))
+}
+
+pub struct After;
+
+impl Operation for After {
+    fn name(&self) -> &str {
+        "after"
+    }
+
+    fn help(&self) -> &str {
+        "Keep rows strictly after a date"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::required("date", ArgKind::Date)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.text(0)?)
+    }
+}
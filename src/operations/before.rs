@@ -1,17 +1,36 @@
-// use chrono::NaiveDate;
-// use crate::DataFrame;
+use chrono::NaiveDate;
+use crate::error::Result;
+use crate::DataFrame;
+use crate::operations::after::parse_date;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
 
-// pub fn before_date(df: &DataFrame, date: NaiveDate) -> DataFrame {
-//     let end_index = df.dates.partition_point(|&d| d < date);
-    
-//     DataFrame {
-//         dates: df.dates[..end_index].to_vec(),
-//         data: df.data[..end_index].to_vec(),
-//         column_names: df.column_names.clone()
-//     }
-// }
+pub fn before_date(df: &DataFrame, date: NaiveDate) -> Result<DataFrame> {
+    let end_index = df.index.partition_point(|time| time.date() < date)?;
+    Ok(df.slice_rows(0..end_index))
+}
 
-// pub fn main(df: &DataFrame, date: &str) -> Result<DataFrame, Box<dyn std::error::Error>> {
-//     let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")?;
-//     Ok(before_date(df, date))
-// }
+pub fn main(df: &DataFrame, date: &str) -> Result<DataFrame> {
+    let date = parse_date(date)?;
+    before_date(df, date)
+}
+
+pub struct Before;
+
+impl Operation for Before {
+    fn name(&self) -> &str {
+        "before"
+    }
+
+    fn help(&self) -> &str {
+        "Keep rows strictly before a date"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::required("date", ArgKind::Date)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.text(0)?)
+    }
+}
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+use crate::align::{align, Join};
+use crate::error::{Error, Result};
+use crate::{DataFrame, DataFrameBuilder, Tags};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Combine two frames element by element with an inner join on dates
+pub fn apply(df1: &DataFrame, df2: &DataFrame, op: fn(f64, f64) -> f64) -> Result<DataFrame> {
+    apply_with(df1, df2, Join::Inner, op)
+}
+
+/// Combine two frames element by element. Rows are aligned on the index with `join`, and
+/// missing rows read as NaN. Columns are matched by name (those of the left frame, plus
+/// those only on the right for an outer join), except that a single-column frame is
+/// applied to every column of the other.
+pub fn apply_with(df1: &DataFrame, df2: &DataFrame, join: Join, op: fn(f64, f64) -> f64) -> Result<DataFrame> {
+    apply_columns(df1, df2, join, |left, right, out| {
+        for ((out, &l), &r) in out.iter_mut().zip(left).zip(right) {
+            *out = op(l, r);
+        }
+    })
+}
+
+/// Combine two frames column by column, for operations such as covariance that need a
+/// whole column at once. Rows and columns are matched as in `apply_with`; `op` gets each
+/// pair of aligned columns and writes the output column.
+pub fn apply_columns<F>(df1: &DataFrame, df2: &DataFrame, join: Join, op: F) -> Result<DataFrame>
+where
+    F: Fn(&[f64], &[f64], &mut [f64]),
+{
+    let alignment = align(&df1.index, &df2.index, join)?;
+    let columns = pair_columns(df1, df2, join)?;
+
+    let names = columns.iter().map(|pair| pair.name.clone()).collect();
+    let tags = columns.iter().map(|pair| pair.tags.clone()).collect();
+    let mut builder = DataFrameBuilder::new(alignment.index.clone(), names, &df1.row_or_column)?.tags(tags);
+    // Missing rows and columns read as NaN
+    let gather = |df: &DataFrame, col: Option<usize>, rows: &[Option<usize>]| -> Vec<f64> {
+        let column: Option<Cow<[f64]>> = col.map(|col| df.column(col));
+        rows.iter()
+            .map(|&row| match (&column, row) {
+                (Some(column), Some(row)) => column[row],
+                _ => f64::NAN,
+            })
+            .collect()
+    };
+    let mut combined = vec![0.0; alignment.index.len()];
+    for (col, pair) in columns.iter().enumerate() {
+        let left = gather(df1, pair.left, &alignment.left);
+        let right = gather(df2, pair.right, &alignment.right);
+        op(&left, &right, &mut combined);
+        builder.set_column(col, &combined);
+    }
+    builder.build()
+}
+
+/// An output column: the columns it combines and its name and tags
+struct ColumnPair {
+    left: Option<usize>,
+    right: Option<usize>,
+    name: String,
+    tags: Tags,
+}
+
+fn pair_columns(df1: &DataFrame, df2: &DataFrame, join: Join) -> Result<Vec<ColumnPair>> {
+    let pair = |left: Option<usize>, right: Option<usize>, from: &DataFrame, col: usize| ColumnPair {
+        left,
+        right,
+        name: from.column_names[col].clone(),
+        tags: from.tags[col].clone(),
+    };
+    if df2.num_columns == 1 {
+        return Ok((0..df1.num_columns).map(|col| pair(Some(col), Some(0), df1, col)).collect());
+    }
+    if df1.num_columns == 1 {
+        return Ok((0..df2.num_columns).map(|col| pair(Some(0), Some(col), df2, col)).collect());
+    }
+
+    let position = |df: &DataFrame, name: &str| df.column_names.iter().position(|other| other == name);
+    let mut columns = Vec::new();
+    for (col, name) in df1.column_names.iter().enumerate() {
+        let right = position(df2, name);
+        if right.is_some() || join != Join::Inner {
+            columns.push(pair(Some(col), right, df1, col));
+        }
+    }
+    if join == Join::Outer {
+        for (col, name) in df2.column_names.iter().enumerate() {
+            if position(df1, name).is_none() {
+                columns.push(pair(None, Some(col), df2, col));
+            }
+        }
+    }
+    if !columns.iter().any(|pair| pair.left.is_some() && pair.right.is_some()) && df1.num_columns > 0 && df2.num_columns > 0 {
+        return Err(Error::Shape("The frames have no column names in common".to_string()));
+    }
+    Ok(columns)
+}
+
+pub fn add(df1: &DataFrame, df2: &DataFrame) -> Result<DataFrame> {
+    apply(df1, df2, |a, b| a + b)
+}
+
+pub fn subtract(df1: &DataFrame, df2: &DataFrame) -> Result<DataFrame> {
+    apply(df1, df2, |a, b| a - b)
+}
+
+pub fn divide(df1: &DataFrame, df2: &DataFrame) -> Result<DataFrame> {
+    apply(df1, df2, |a, b| a / b)
+}
+
+/// An element-wise operation between two frames. `op x` combines the current frame
+/// with variable `x`, while `op x y` combines variables `x` and `y`; `join=` picks how
+/// their dates are aligned.
+pub struct BinaryOp {
+    name: &'static str,
+    help: &'static str,
+    op: fn(f64, f64) -> f64,
+}
+
+impl BinaryOp {
+    pub const fn new(name: &'static str, help: &'static str, op: fn(f64, f64) -> f64) -> Self {
+        BinaryOp { name, help, op }
+    }
+}
+
+impl Operation for BinaryOp {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn help(&self) -> &str {
+        self.help
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("x", ArgKind::Frame),
+            ArgSpec::optional("y", ArgKind::Frame),
+            ArgSpec::with_default("join", ArgKind::Text, "inner"),
+        ];
+        ARGS
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        apply_with(&inputs[0], &inputs[1], args.parse(0)?, self.op)
+    }
+}
@@ -1,23 +1,192 @@
-// use crate::DataFrame;
-
-// pub fn filter_columns(df: &DataFrame, column_names: &[String]) -> DataFrame {
-//     let selected_indices: Vec<usize> = column_names
-//         .iter()
-//         .filter_map(|name| df.column_names.iter().position(|n| n == name))
-//         .collect();
-
-//     DataFrame {
-//         dates: df.dates.clone(),
-//         data: df.data.iter()
-//             .map(|row| selected_indices.iter().map(|&i| row[i]).collect())
-//             .collect(),
-//         column_names: selected_indices.iter().map(|&i| df.column_names[i].clone()).collect(),
-//     }
-// }
-
-// pub fn main(df: &DataFrame, columns: &[String]) -> Result<DataFrame, Box<dyn std::error::Error>> {
-//     if df.data.is_empty() {
-//         return Err("No data available".into());
-//     }
-//     Ok(filter_columns(df, columns))
-// }
+use regex::Regex;
+use crate::error::{Error, Result};
+use crate::{DataFrame, Tags};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// How `cgrep` applies its patterns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GrepOptions {
+    pub invert: bool,                   // Keep the columns that match no pattern (`-v`)
+    pub strict: bool,                   // Fail if a pattern matches no column (`-s`, `--strict`)
+}
+
+/// One column pattern: an exact name, a glob such as `US*`, an anchored regex such as
+/// `US.*`, or a tag such as `tag:asset_class=rates` (or `tag:sector` for any value). A `re:`
+/// or `glob:` prefix picks the kind; otherwise the name of a column is matched exactly, even
+/// if it has metacharacters such as `CL(1)`, and failing that regex metacharacters mean a
+/// regex and `*`, `?` or `[` a glob.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Exact(String),
+    Regex(Regex),
+    Tag(String, Option<String>),        // Key and, unless any value matches, the value
+}
+
+impl Pattern {
+    /// The pattern as written, without knowing the columns: bare names are never exact
+    /// once they contain metacharacters
+    pub fn parse(pattern: &str) -> Result<Self> {
+        if let Some(tag) = pattern.strip_prefix("tag:") {
+            return Ok(match tag.split_once('=') {
+                Some((key, value)) => Pattern::Tag(key.to_string(), Some(value.to_string())),
+                None => Pattern::Tag(tag.to_string(), None),
+            });
+        }
+        if let Some(regex) = pattern.strip_prefix("re:") {
+            return Self::regex(regex, pattern);
+        }
+        if let Some(glob) = pattern.strip_prefix("glob:") {
+            return Self::regex(&glob_to_regex(glob), pattern);
+        }
+        if pattern.contains(['.', '+', '^', '$', '(', ')', '|', '\\', '{', '}']) {
+            Self::regex(pattern, pattern)
+        } else if pattern.contains(['*', '?', '[']) {
+            Self::regex(&glob_to_regex(pattern), pattern)
+        } else {
+            Ok(Pattern::Exact(pattern.to_string()))
+        }
+    }
+
+    /// The pattern for selecting among columns called `names`: an unprefixed pattern that
+    /// is one of the names selects that column exactly
+    pub fn for_columns(pattern: &str, names: &[String]) -> Result<Self> {
+        let prefixed = ["tag:", "re:", "glob:"].iter().any(|prefix| pattern.starts_with(prefix));
+        if !prefixed && names.iter().any(|name| name == pattern) {
+            return Ok(Pattern::Exact(pattern.to_string()));
+        }
+        Self::parse(pattern)
+    }
+
+    fn regex(regex: &str, pattern: &str) -> Result<Self> {
+        Regex::new(&format!("^(?:{})$", regex))
+            .map(Pattern::Regex)
+            .map_err(|e| Error::InvalidValue(format!("Invalid column pattern '{}': {}", pattern, e)))
+    }
+
+    /// Whether a column with this name and these tags matches
+    pub fn matches(&self, name: &str, tags: &Tags) -> bool {
+        match self {
+            Pattern::Exact(exact) => exact == name,
+            Pattern::Regex(regex) => regex.is_match(name),
+            Pattern::Tag(key, value) => match (tags.get(key), value) {
+                (Some(tag), Some(value)) => tag == value,
+                (found, None) => found.is_some(),
+                (None, Some(_)) => false,
+            },
+        }
+    }
+}
+
+/// Translate a shell glob into regex syntax
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut in_class = false;
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if !in_class => regex.push_str(".*"),
+            '?' if !in_class => regex.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[');
+                if chars.next_if_eq(&'!').is_some() {
+                    regex.push('^');
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                regex.push(']');
+            }
+            // Ranges such as `0-9` keep their dash inside a class
+            '-' if in_class => regex.push('-'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+/// Positions of the columns selected by `patterns`: in pattern order, each column once, or
+/// with `invert` every column that matches no pattern, in frame order
+pub fn matching_columns(df: &DataFrame, patterns: &[String], options: GrepOptions) -> Result<Vec<usize>> {
+    let mut matched = vec![false; df.num_columns];
+    let mut selected = Vec::new();
+    for text in patterns {
+        let pattern = Pattern::for_columns(text, &df.column_names)?;
+        let mut found = false;
+        for (col_index, (name, tags)) in df.column_names.iter().zip(&df.tags).enumerate() {
+            if pattern.matches(name, tags) {
+                found = true;
+                if !matched[col_index] {
+                    matched[col_index] = true;
+                    selected.push(col_index);
+                }
+            }
+        }
+        if options.strict && !found {
+            return Err(Error::MissingColumn(text.clone()));
+        }
+    }
+    if options.invert {
+        selected = (0..df.num_columns).filter(|&col_index| !matched[col_index]).collect();
+    }
+    Ok(selected)
+}
+
+/// The selected columns as a view sharing the input's mapping
+pub fn grep(df: &DataFrame, patterns: &[String], options: GrepOptions) -> Result<DataFrame> {
+    Ok(df.select_columns(&matching_columns(df, patterns, options)?))
+}
+
+/// Keep the columns matching any of `column_names`, ignoring those that match nothing
+pub fn filter_columns(df: &DataFrame, column_names: &[String]) -> Result<DataFrame> {
+    grep(df, column_names, GrepOptions::default())
+}
+
+/// Split leading `-v`, `-s` and `--strict` flags from the patterns; `--` ends the flags
+pub fn parse_flags(args: &[String]) -> Result<(GrepOptions, &[String])> {
+    let mut options = GrepOptions::default();
+    for (i, arg) in args.iter().enumerate() {
+        match arg.as_str() {
+            "-v" => options.invert = true,
+            "-s" | "--strict" => options.strict = true,
+            "--" => return Ok((options, &args[i + 1..])),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(Error::InvalidValue(format!("Unknown cgrep flag '{}'", flag)));
+            }
+            _ => return Ok((options, &args[i..])),
+        }
+    }
+    Ok((options, &[]))
+}
+
+pub fn main(df: &DataFrame, columns: &[String]) -> Result<DataFrame> {
+    if df.num_rows == 0 {
+        return Err(Error::NoInput("No data available".to_string()));
+    }
+    let (options, patterns) = parse_flags(columns)?;
+    if patterns.is_empty() {
+        return Err(Error::InvalidValue("cgrep needs at least one column pattern".to_string()));
+    }
+    grep(df, patterns, options)
+}
+
+pub struct Cgrep;
+
+impl Operation for Cgrep {
+    fn name(&self) -> &str {
+        "cgrep"
+    }
+
+    fn help(&self) -> &str {
+        "Keep the columns matching names, globs (US*), regexes (US.*) or tags (tag:asset_class=rates), in pattern order; -v keeps the rest, -s fails on a pattern that matches nothing"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::variadic("columns", ArgKind::Text)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.rest(0))
+    }
+}
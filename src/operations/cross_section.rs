@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+use crate::{DataFrame, DataFrameBuilder};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+// Operations across the columns of each row, e.g. ranking assets on each date. NaNs are
+// left out and stay NaN. With `by=<tag>` each group of columns sharing a value of that tag
+// is treated as its own cross-section; columns without the tag form one more group.
+
+/// A transformation of the values of one row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    Rank { pct: bool },                 // 1 for the smallest, ties averaged; divided by the count with `pct`
+    Zscore,                             // Distance from the mean in sample standard deviations
+    Demean,
+    Winsor(f64),                        // Clip to the p and 1 - p quantiles
+    Normalize,                          // Divide by the sum of absolute values
+    TopN(i64),                          // 1 for the n largest values (smallest if negative), 0 for the rest
+}
+
+impl Transform {
+    fn validate(self) -> Result<Self> {
+        match self {
+            Transform::Winsor(p) if !(0.0..=0.5).contains(&p) => {
+                Err(Error::InvalidValue(format!("Winsorizing needs p between 0 and 0.5, got {}", p)))
+            }
+            Transform::TopN(0) => Err(Error::InvalidValue("xtopn needs a non-zero count".to_string())),
+            _ => Ok(self),
+        }
+    }
+}
+
+/// Positions of the columns in each cross-section: all columns, or grouped by the value
+/// of tag `by`
+pub fn groups(df: &DataFrame, by: Option<&str>) -> Vec<Vec<usize>> {
+    let Some(key) = by else {
+        return vec![(0..df.num_columns).collect()];
+    };
+    let mut groups: BTreeMap<Option<&String>, Vec<usize>> = BTreeMap::new();
+    for (col_index, tags) in df.tags.iter().enumerate() {
+        groups.entry(tags.get(key)).or_default().push(col_index);
+    }
+    groups.into_values().collect()
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    match sorted.get(below + 1) {
+        Some(&above) => sorted[below] + (above - sorted[below]) * position.fract(),
+        None => sorted[below],
+    }
+}
+
+/// Apply `transform` to one cross-section, writing NaN where a value is missing
+pub fn transform_row(values: &[f64], out: &mut [f64], transform: Transform) {
+    out.fill(f64::NAN);
+    let mut valid: Vec<usize> = (0..values.len()).filter(|&i| !values[i].is_nan()).collect();
+    if valid.is_empty() {
+        return;
+    }
+    let n = valid.len() as f64;
+    let mean = valid.iter().map(|&i| values[i]).sum::<f64>() / n;
+    match transform {
+        Transform::Rank { pct } => {
+            valid.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+            let mut start = 0;
+            while start < valid.len() {
+                let tied = valid[start..].iter().take_while(|&&i| values[i] == values[valid[start]]).count();
+                let rank = start as f64 + (tied as f64 + 1.0) / 2.0;
+                for &i in &valid[start..start + tied] {
+                    out[i] = if pct { rank / n } else { rank };
+                }
+                start += tied;
+            }
+        }
+        Transform::Zscore => {
+            let std = (valid.iter().map(|&i| (values[i] - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            if std > 0.0 {
+                valid.iter().for_each(|&i| out[i] = (values[i] - mean) / std);
+            }
+        }
+        Transform::Demean => valid.iter().for_each(|&i| out[i] = values[i] - mean),
+        Transform::Winsor(p) => {
+            let mut sorted: Vec<f64> = valid.iter().map(|&i| values[i]).collect();
+            sorted.sort_by(f64::total_cmp);
+            let (low, high) = (quantile(&sorted, p), quantile(&sorted, 1.0 - p));
+            valid.iter().for_each(|&i| out[i] = values[i].clamp(low, high));
+        }
+        Transform::Normalize => {
+            let total: f64 = valid.iter().map(|&i| values[i].abs()).sum();
+            if total > 0.0 {
+                valid.iter().for_each(|&i| out[i] = values[i] / total);
+            }
+        }
+        Transform::TopN(count) => {
+            // Stable, so ties at the cut go to the leftmost columns
+            valid.sort_by(|&a, &b| if count > 0 { values[b].total_cmp(&values[a]) } else { values[a].total_cmp(&values[b]) });
+            let chosen = (count.unsigned_abs() as usize).min(valid.len());
+            for (position, &i) in valid.iter().enumerate() {
+                out[i] = if position < chosen { 1.0 } else { 0.0 };
+            }
+        }
+    }
+}
+
+/// Apply `transform` across the columns of every row, within the groups of tag `by`
+pub fn cross_section(df: &DataFrame, transform: Transform, by: Option<&str>) -> Result<DataFrame> {
+    let transform = transform.validate()?;
+    let groups = groups(df, by);
+    let mut output = DataFrameBuilder::like(df)?;
+    let (mut values, mut out) = (Vec::with_capacity(df.num_columns), vec![0.0; df.num_columns]);
+    for row_index in 0..df.num_rows {
+        let row = df.row(row_index);
+        for group in &groups {
+            values.clear();
+            values.extend(group.iter().map(|&col_index| row[col_index]));
+            transform_row(&values, &mut out[..group.len()], transform);
+            for (&col_index, &value) in group.iter().zip(&out) {
+                output.set(row_index, col_index, value);
+            }
+        }
+    }
+    output.build()
+}
+
+pub fn main(df: &DataFrame, transform: Transform, by: Option<&str>) -> Result<DataFrame> {
+    if df.num_columns == 0 {
+        return Err(Error::NoInput("No columns to compare".to_string()));
+    }
+    cross_section(df, transform, by)
+}
+
+/// A cross-sectional transformation without parameters, registered under its own verb
+pub struct CrossSection {
+    name: &'static str,
+    help: &'static str,
+    transform: Transform,
+}
+
+impl CrossSection {
+    pub const fn new(name: &'static str, help: &'static str, transform: Transform) -> Self {
+        CrossSection { name, help, transform }
+    }
+}
+
+impl Operation for CrossSection {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn help(&self) -> &str {
+        self.help
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::optional("by", ArgKind::Text)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], self.transform, args.get(0))
+    }
+}
+
+pub struct XRank;
+
+impl Operation for XRank {
+    fn name(&self) -> &str {
+        "xrank"
+    }
+
+    fn help(&self) -> &str {
+        "Rank the columns of each row from 1 for the smallest, averaging ties; pct=true divides by the count, e.g. xrank sector pct=true"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::optional("by", ArgKind::Text),
+            ArgSpec::with_default("pct", ArgKind::Text, "false"),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        // `pct` always has a value, so `by` was given when there are two
+        let pct = args.len() - 1;
+        main(&inputs[0], Transform::Rank { pct: args.parse(pct)? }, if pct == 1 { args.get(0) } else { None })
+    }
+}
+
+pub struct XWinsor;
+
+impl Operation for XWinsor {
+    fn name(&self) -> &str {
+        "xwinsor"
+    }
+
+    fn help(&self) -> &str {
+        "Clip each row to its p and 1 - p quantiles across columns"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("p", ArgKind::Number),
+            ArgSpec::optional("by", ArgKind::Text),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], Transform::Winsor(args.parse(0)?), args.get(1))
+    }
+}
+
+pub struct XTopN;
+
+impl Operation for XTopN {
+    fn name(&self) -> &str {
+        "xtopn"
+    }
+
+    fn help(&self) -> &str {
+        "Mark the n largest columns of each row with 1 and the rest with 0; a negative n marks the smallest"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("n", ArgKind::Integer),
+            ArgSpec::optional("by", ArgKind::Text),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], Transform::TopN(args.parse(0)?), args.get(1))
+    }
+}
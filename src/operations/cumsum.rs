@@ -1,33 +1,43 @@
-// use crate::DataFrame;
-
-// pub fn cumulative_sum(df: &mut DataFrame, start_number: f64) -> DataFrame {
-//     // Remove rows with NaN values
-//     df.data.retain(|row| row.iter().all(|&x| !x.is_nan()));
-//     df.dates = df.dates[..df.data.len()].to_vec();
-
-//     if !df.data.is_empty() {
-//         for value in &mut df.data[0] {
-//             *value += start_number;
-//         }
-//     }
-
-//     let mut cumsum_data = Vec::with_capacity(df.data.len());
-//     let mut running_sum = vec![0.0; df.data[0].len()];
-
-//     for row in &df.data {
-//         for (i, &value) in row.iter().enumerate() {
-//             running_sum[i] += value;
-//         }
-//         cumsum_data.push(running_sum.clone());
-//     }
-
-//     DataFrame {
-//         dates: df.dates.clone(),
-//         data: cumsum_data,
-//         column_names: df.column_names.clone()
-//     }
-// }
-
-// pub fn main(df: &mut DataFrame, start_number: f64) -> Result<DataFrame, Box<dyn std::error::Error>> {
-//     Ok(cumulative_sum(df, start_number))
-// }
+use crate::error::Result;
+use crate::{DataFrame, Engine};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub fn cumulative_sum(df: &DataFrame, start_number: f64) -> Result<DataFrame> {
+    // Remove rows with NaN values
+    let kept_rows: Vec<usize> = (0..df.num_rows)
+        .filter(|&row| df.row(row).iter().all(|x| !x.is_nan()))
+        .collect();
+
+    Engine::parallel_process_into(df, df.index.select(&kept_rows), |values, sums| {
+        let mut running_sum = start_number;
+        for (out, &row) in sums.iter_mut().zip(&kept_rows) {
+            running_sum += values[row];
+            *out = running_sum;
+        }
+    })
+}
+
+pub fn main(df: &DataFrame, start_number: f64) -> Result<DataFrame> {
+    cumulative_sum(df, start_number)
+}
+
+pub struct Cumsum;
+
+impl Operation for Cumsum {
+    fn name(&self) -> &str {
+        "cumsum"
+    }
+
+    fn help(&self) -> &str {
+        "Cumulative sum from a starting value, dropping rows with missing values"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::with_default("start", ArgKind::Number, "0")];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.parse(0)?)
+    }
+}
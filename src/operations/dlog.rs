@@ -1,77 +1,59 @@
-use std::sync::Arc;
-
-use memmap2::MmapMut;
+use crate::error::Result;
 use crate::DataFrame;
+use crate::registry::{Args, Operation};
+use crate::engine::Engine;
 
-pub fn dlog(input_df: &DataFrame) -> Result<DataFrame, Box<dyn std::error::Error>> {
-    // Output dimensions remain the same as input dimensions
-    let num_rows = input_df.num_rows;
-    let num_columns = input_df.num_columns;
-
-    let column_names = input_df.column_names.clone();
-    let row_names = input_df.row_names.clone();
-
-    // Calculate size for the new memory map (8 bytes per float value)
-    let output_size = num_rows * num_columns * 8;
+pub fn dlog(input_df: &DataFrame) -> Result<DataFrame> {
+    Engine::parallel_process(input_df, dlog_column)
+}
 
-    // Create an anonymous memory map for the output DataFrame
-    let mut mmap_out = { MmapMut::map_anon(output_size)? };
+/// Log returns down one column; the first value is NaN as there is no previous value
+pub fn dlog_column(input: &[f64], output: &mut [f64]) {
+    if let Some(first) = output.first_mut() {
+        *first = f64::NAN;
+    }
+    for (ret, pair) in output.iter_mut().skip(1).zip(input.windows(2)) {
+        *ret = log_return(pair[0], pair[1]);
+    }
+}
 
-    // Perform log return calculations
-    for block_no in 0..input_df.offsets.len() {
-        for block_idx in 1..input_df.offsets[block_no].len() {
-            let prev_offset = input_df.offsets[block_no][block_idx-1];
-            let curr_offset = input_df.offsets[block_no][block_idx];
+/// ln(curr / prev), or NaN unless both prices are positive
+fn log_return(prev_value: f64, curr_value: f64) -> f64 {
+    if prev_value > 0.0 && curr_value > 0.0 {
+        (curr_value / prev_value).ln()
+    } else {
+        f64::NAN
+    }
+}
 
-            let prev_value = f64::from_le_bytes(input_df.mmap[prev_offset..prev_offset + 8].try_into()?);
-            let curr_value = f64::from_le_bytes(input_df.mmap[curr_offset..curr_offset + 8].try_into()?);
+pub struct Dlog;
 
-            let log_return = if prev_value > 0.0 && curr_value > 0.0 {
-                (curr_value / prev_value).ln()
-            } else {
-                f64::NAN
-            };
+impl Operation for Dlog {
+    fn name(&self) -> &str {
+        "dlog"
+    }
 
-            //let output_offset = row_index * num_columns * 8 + col_index * 8;
-            mmap_out[curr_offset..curr_offset + 8].copy_from_slice(&log_return.to_le_bytes());
-        }
+    fn help(&self) -> &str {
+        "Log returns between consecutive rows"
+    }
 
-        // Fill first column with NaN (no previous value for log return)
-        let first_offset = block_no * num_columns * 8;
-        mmap_out[first_offset..first_offset + 8].copy_from_slice(&f64::NAN.to_le_bytes());
+    fn apply(&self, inputs: &[DataFrame], _args: &Args) -> Result<DataFrame> {
+        dlog(&inputs[0])
     }
-    Ok(DataFrame {
-        mmap: Arc::new(mmap_out.make_read_only()?),
-        num_rows,
-        num_columns,
-        column_names,
-        row_names,
-        row_or_column: input_df.row_or_column.clone(),
-        offsets: input_df.offsets.clone(),
-    })
 }
 
-pub fn dlog_block(input: &[u8], output: &mut [u8], offsets: &[usize]) {
-    for i in 1..offsets.len() {
-        let prev_offset = offsets[i - 1];
-        let curr_offset = offsets[i];
+pub struct DlogMultithread;
 
-        // Read previous and current values
-        let prev_value = f64::from_le_bytes(input[prev_offset..prev_offset + 8].try_into().unwrap());
-        let curr_value = f64::from_le_bytes(input[curr_offset..curr_offset + 8].try_into().unwrap());
-
-        // Compute log return
-        let log_return = if prev_value > 0.0 && curr_value > 0.0 {
-            (curr_value / prev_value).ln()
-        } else {
-            f64::NAN
-        };
+impl Operation for DlogMultithread {
+    fn name(&self) -> &str {
+        "dlog_multithread"
+    }
 
-        // Write the result to the output memory map
-        output[curr_offset..curr_offset + 8].copy_from_slice(&log_return.to_le_bytes());
+    fn help(&self) -> &str {
+        "Alias of dlog, kept for existing scripts; dlog already runs on worker threads"
     }
 
-    // Set the first value in the block to NaN
-    let first_offset = offsets[0];
-    output[first_offset..first_offset + 8].copy_from_slice(&f64::NAN.to_le_bytes());
+    fn apply(&self, inputs: &[DataFrame], _args: &Args) -> Result<DataFrame> {
+        dlog(&inputs[0])
+    }
 }
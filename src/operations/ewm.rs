@@ -0,0 +1,319 @@
+use crate::align::Join;
+use crate::error::{Error, Result};
+use crate::{DataFrame, Engine};
+use crate::operations::binary;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+// Exponentially weighted statistics, matching pandas' `ewm(...)`. The weight of a value
+// decays by (1 - alpha) per row after it. With `adjust` the weights are normalised over
+// the values seen so far; without it the statistic follows the recursion
+// y = (1 - alpha) * y + alpha * x. NaNs are skipped, but unless `ignore_na` is set they
+// still count as rows for the decay. Before the first value, and until `min_periods`
+// values have been seen, results are NaN; after that a NaN carries the last result.
+
+/// How quickly weights decay, given as in pandas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decay {
+    Com(f64),                           // Center of mass: alpha = 1 / (1 + com)
+    Span(f64),                          // alpha = 2 / (span + 1)
+    Halflife(f64),                      // Rows for a weight to halve
+    Alpha(f64),
+}
+
+impl Decay {
+    /// A decay from its kind (`com`, `span`, `halflife` or `alpha`) and value
+    pub fn parse(kind: &str, value: f64) -> Result<Self> {
+        match kind {
+            "com" => Ok(Decay::Com(value)),
+            "span" => Ok(Decay::Span(value)),
+            "halflife" => Ok(Decay::Halflife(value)),
+            "alpha" => Ok(Decay::Alpha(value)),
+            _ => Err(Error::InvalidValue(format!("Unknown decay '{}': expected com, span, halflife or alpha", kind))),
+        }
+    }
+
+    /// The smoothing factor, checked to be in (0, 1]
+    pub fn alpha(self) -> Result<f64> {
+        let (alpha, valid) = match self {
+            Decay::Com(com) => (1.0 / (1.0 + com), com >= 0.0),
+            Decay::Span(span) => (2.0 / (span + 1.0), span >= 1.0),
+            Decay::Halflife(halflife) => (1.0 - (-std::f64::consts::LN_2 / halflife).exp(), halflife > 0.0),
+            Decay::Alpha(alpha) => (alpha, alpha > 0.0 && alpha <= 1.0),
+        };
+        if valid {
+            Ok(alpha)
+        } else {
+            Err(Error::InvalidValue(format!("Invalid decay {:?}: com must be >= 0, span >= 1, halflife > 0 and alpha in (0, 1]", self)))
+        }
+    }
+}
+
+/// Options shared by the exponentially weighted statistics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ewm {
+    pub alpha: f64,
+    pub adjust: bool,                   // Normalise weights over the values seen so far
+    pub ignore_na: bool,                // Decay only across values, not across NaN rows
+    pub min_periods: usize,             // Values needed before a result is given
+}
+
+impl Ewm {
+    /// pandas' defaults: adjusted, decaying across NaNs, from the first value
+    pub fn new(decay: Decay) -> Result<Self> {
+        Ok(Ewm { alpha: decay.alpha()?, adjust: true, ignore_na: false, min_periods: 0 })
+    }
+
+    /// Weight of each new value
+    fn new_weight(&self) -> f64 {
+        if self.adjust { 1.0 } else { self.alpha }
+    }
+}
+
+/// Exponentially weighted mean of one column
+pub fn mean_column(values: &[f64], out: &mut [f64], ewm: &Ewm) {
+    let (new_weight, decay) = (ewm.new_weight(), 1.0 - ewm.alpha);
+    let min_periods = ewm.min_periods.max(1);
+    let (mut mean, mut old_weight, mut observations) = (f64::NAN, 1.0, 0);
+    for (&x, out) in values.iter().zip(out.iter_mut()) {
+        let observed = !x.is_nan();
+        observations += usize::from(observed);
+        if mean.is_nan() {
+            mean = x;
+        } else if observed || !ewm.ignore_na {
+            old_weight *= decay;
+            if observed {
+                // Equal values are kept as they are so constant series stay exact
+                if mean != x {
+                    mean = (old_weight * mean + new_weight * x) / (old_weight + new_weight);
+                }
+                old_weight = if ewm.adjust { old_weight + new_weight } else { 1.0 };
+            }
+        }
+        *out = if observations >= min_periods { mean } else { f64::NAN };
+    }
+}
+
+/// Exponentially weighted covariance of two aligned columns, over rows where both have
+/// values. Unless `bias` is set the result is corrected for the effective sample size, so
+/// it is NaN until there are two values.
+pub fn cov_columns(x: &[f64], y: &[f64], out: &mut [f64], ewm: &Ewm, bias: bool) {
+    let (new_weight, decay) = (ewm.new_weight(), 1.0 - ewm.alpha);
+    let min_periods = ewm.min_periods.max(1);
+    let (mut mean_x, mut mean_y, mut cov) = (f64::NAN, f64::NAN, 0.0);
+    let (mut sum_weights, mut sum_squared_weights, mut old_weight) = (1.0, 1.0, 1.0);
+    let mut observations = 0;
+    for ((&cur_x, &cur_y), out) in x.iter().zip(y).zip(out.iter_mut()) {
+        let observed = !cur_x.is_nan() && !cur_y.is_nan();
+        observations += usize::from(observed);
+        if mean_x.is_nan() {
+            if observed {
+                (mean_x, mean_y) = (cur_x, cur_y);
+            }
+        } else if observed || !ewm.ignore_na {
+            sum_weights *= decay;
+            sum_squared_weights *= decay * decay;
+            old_weight *= decay;
+            if observed {
+                let (old_mean_x, old_mean_y) = (mean_x, mean_y);
+                if mean_x != cur_x {
+                    mean_x = (old_weight * old_mean_x + new_weight * cur_x) / (old_weight + new_weight);
+                }
+                if mean_y != cur_y {
+                    mean_y = (old_weight * old_mean_y + new_weight * cur_y) / (old_weight + new_weight);
+                }
+                cov = (old_weight * (cov + (old_mean_x - mean_x) * (old_mean_y - mean_y))
+                    + new_weight * (cur_x - mean_x) * (cur_y - mean_y))
+                    / (old_weight + new_weight);
+                sum_weights += new_weight;
+                sum_squared_weights += new_weight * new_weight;
+                old_weight += new_weight;
+                if !ewm.adjust {
+                    sum_weights /= old_weight;
+                    sum_squared_weights /= old_weight * old_weight;
+                    old_weight = 1.0;
+                }
+            }
+        }
+        *out = if observations < min_periods {
+            f64::NAN
+        } else if bias {
+            cov
+        } else if observations < 2 {
+            // Decaying the weights can leave rounding error where the correction should be 0 / 0
+            f64::NAN
+        } else {
+            let numerator = sum_weights * sum_weights;
+            let denominator = numerator - sum_squared_weights;
+            if denominator > 0.0 { numerator / denominator * cov } else { f64::NAN }
+        };
+    }
+}
+
+/// Exponentially weighted correlation of two aligned columns, over rows where both have values
+pub fn corr_columns(x: &[f64], y: &[f64], out: &mut [f64], ewm: &Ewm) {
+    // Both variances are taken over the rows the covariance uses
+    let masked = |a: &[f64], b: &[f64]| -> Vec<f64> { a.iter().zip(b).map(|(&a, &b)| if b.is_nan() { f64::NAN } else { a }).collect() };
+    let (x, y) = (masked(x, y), masked(y, x));
+    let (mut var_x, mut var_y) = (vec![0.0; x.len()], vec![0.0; y.len()]);
+    cov_columns(&x, &y, out, ewm, true);
+    cov_columns(&x, &x, &mut var_x, ewm, true);
+    cov_columns(&y, &y, &mut var_y, ewm, true);
+    for ((out, vx), vy) in out.iter_mut().zip(var_x).zip(var_y) {
+        let denominator = (vx * vy).sqrt();
+        *out = if denominator > 0.0 { *out / denominator } else { f64::NAN };
+    }
+}
+
+pub fn ema(df: &DataFrame, ewm: &Ewm) -> Result<DataFrame> {
+    Engine::parallel_process(df, |values, out| mean_column(values, out, ewm))
+}
+
+/// Exponentially weighted standard deviation of each column
+pub fn ewvol(df: &DataFrame, ewm: &Ewm, bias: bool) -> Result<DataFrame> {
+    Engine::parallel_process(df, |values, out| {
+        cov_columns(values, values, out, ewm, bias);
+        out.iter_mut().for_each(|var| *var = var.sqrt());
+    })
+}
+
+/// Exponentially weighted covariance of matching columns, aligned on dates with `join`
+pub fn ewcov(df1: &DataFrame, df2: &DataFrame, ewm: &Ewm, bias: bool, join: Join) -> Result<DataFrame> {
+    binary::apply_columns(df1, df2, join, |x, y, out| cov_columns(x, y, out, ewm, bias))
+}
+
+/// Exponentially weighted correlation of matching columns, aligned on dates with `join`
+pub fn ewcorr(df1: &DataFrame, df2: &DataFrame, ewm: &Ewm, join: Join) -> Result<DataFrame> {
+    binary::apply_columns(df1, df2, join, |x, y, out| corr_columns(x, y, out, ewm))
+}
+
+/// Decay and options from the arguments `decay value adjust ignore_na min_periods`
+fn options(args: &Args) -> Result<Ewm> {
+    Ok(Ewm {
+        adjust: args.parse(2)?,
+        ignore_na: args.parse(3)?,
+        min_periods: args.parse(4)?,
+        ..Ewm::new(Decay::parse(args.text(0)?, args.parse(1)?)?)?
+    })
+}
+
+pub struct Ema;
+
+impl Operation for Ema {
+    fn name(&self) -> &str {
+        "ema"
+    }
+
+    fn help(&self) -> &str {
+        "Exponentially weighted moving average, with decay given by com, span, halflife or alpha as in pandas"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("decay", ArgKind::Text),
+            ArgSpec::required("value", ArgKind::Number),
+            ArgSpec::with_default("adjust", ArgKind::Text, "true"),
+            ArgSpec::with_default("ignore_na", ArgKind::Text, "false"),
+            ArgSpec::with_default("min_periods", ArgKind::Count, "0"),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        ema(&inputs[0], &options(args)?)
+    }
+}
+
+pub struct EwVol;
+
+impl Operation for EwVol {
+    fn name(&self) -> &str {
+        "ewvol"
+    }
+
+    fn help(&self) -> &str {
+        "Exponentially weighted standard deviation, bias-corrected unless bias=true"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("decay", ArgKind::Text),
+            ArgSpec::required("value", ArgKind::Number),
+            ArgSpec::with_default("adjust", ArgKind::Text, "true"),
+            ArgSpec::with_default("ignore_na", ArgKind::Text, "false"),
+            ArgSpec::with_default("min_periods", ArgKind::Count, "0"),
+            ArgSpec::with_default("bias", ArgKind::Text, "false"),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        ewvol(&inputs[0], &options(args)?, args.parse(5)?)
+    }
+}
+
+pub struct EwCov;
+
+impl Operation for EwCov {
+    fn name(&self) -> &str {
+        "ewcov"
+    }
+
+    fn help(&self) -> &str {
+        "Exponentially weighted covariance of the current frame with another, column by column"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("y", ArgKind::Frame),
+            ArgSpec::required("decay", ArgKind::Text),
+            ArgSpec::required("value", ArgKind::Number),
+            ArgSpec::with_default("adjust", ArgKind::Text, "true"),
+            ArgSpec::with_default("ignore_na", ArgKind::Text, "false"),
+            ArgSpec::with_default("min_periods", ArgKind::Count, "0"),
+            ArgSpec::with_default("bias", ArgKind::Text, "false"),
+            ArgSpec::with_default("join", ArgKind::Text, "inner"),
+        ];
+        ARGS
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        ewcov(&inputs[0], &inputs[1], &options(args)?, args.parse(5)?, args.parse(6)?)
+    }
+}
+
+pub struct EwCorr;
+
+impl Operation for EwCorr {
+    fn name(&self) -> &str {
+        "ewcorr"
+    }
+
+    fn help(&self) -> &str {
+        "Exponentially weighted correlation of the current frame with another, column by column"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("y", ArgKind::Frame),
+            ArgSpec::required("decay", ArgKind::Text),
+            ArgSpec::required("value", ArgKind::Number),
+            ArgSpec::with_default("adjust", ArgKind::Text, "true"),
+            ArgSpec::with_default("ignore_na", ArgKind::Text, "false"),
+            ArgSpec::with_default("min_periods", ArgKind::Count, "0"),
+            ArgSpec::with_default("join", ArgKind::Text, "inner"),
+        ];
+        ARGS
+    }
+
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        ewcorr(&inputs[0], &inputs[1], &options(args)?, args.parse(5)?)
+    }
+}
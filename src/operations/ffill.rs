@@ -1,25 +1,35 @@
-// use crate::DataFrame;
+use crate::error::Result;
+use crate::{DataFrame, Engine};
+use crate::registry::{Args, Operation};
 
-// pub fn ffill(df: &DataFrame) -> DataFrame {
-//     let mut result = DataFrame {
-//         dates: df.dates.clone(),
-//         data: vec![vec![f64::NAN; df.data[0].len()]; df.data.len()],
-//         column_names: df.column_names.clone()
-//     };
+pub fn ffill(df: &DataFrame) -> Result<DataFrame> {
+    Engine::parallel_process(df, |values, filled| {
+        let mut last_valid = f64::NAN;
+        for (out, &value) in filled.iter_mut().zip(values) {
+            if !value.is_nan() {
+                last_valid = value;
+            }
+            *out = last_valid;
+        }
+    })
+}
 
-//     for col in 0..df.data[0].len() {
-//         let mut last_valid = f64::NAN;
-//         for row in 0..df.data.len() {
-//             if !df.data[row][col].is_nan() {
-//                 last_valid = df.data[row][col];
-//             }
-//             result.data[row][col] = last_valid;
-//         }
-//     }
+pub fn main(df: &DataFrame) -> Result<DataFrame> {
+    ffill(df)
+}
 
-//     result
-// }
+pub struct Ffill;
 
-// pub fn main(df: &DataFrame) -> DataFrame {
-//     ffill(df)
-// }
+impl Operation for Ffill {
+    fn name(&self) -> &str {
+        "ffill"
+    }
+
+    fn help(&self) -> &str {
+        "Forward fill missing values down each column"
+    }
+
+    fn apply(&self, inputs: &[DataFrame], _args: &Args) -> Result<DataFrame> {
+        main(&inputs[0])
+    }
+}
@@ -1,43 +1,75 @@
-// use crate::DataFrame;
-// use chrono::NaiveDate;
-// use csv::Reader;
-// use std::fs::File;
-// use std::error::Error;
-
-// pub fn load(filename: &str) -> Result<DataFrame, Box<dyn Error>> {
-//     let file = File::open(filename)?;
-//     let mut rdr = Reader::from_reader(file);
-    
-//     let headers = rdr.headers()?.clone();
-//     let date_index = headers.iter().position(|h| h == "DATE")
-//         .ok_or("DATE column not found")?;
-
-//     let mut dates = Vec::new();
-//     let mut data = Vec::new();
-
-//     for result in rdr.records() {
-//         let record = result?;
-//         let date = NaiveDate::parse_from_str(&record[date_index], "%Y-%m-%d")?;
-//         dates.push(date);
-
-//         let row: Vec<f64> = record.iter()
-//             .enumerate()
-//             .filter(|&(i, _)| i != date_index)
-//             .map(|(_, val)| val.parse::<f64>().unwrap_or(f64::NAN))
-//             .collect();
-
-//         data.push(row);
-//     }
-
-//     let column_names: Vec<String> = headers.iter()
-//         .enumerate()
-//         .filter(|&(i, _)| i != date_index)
-//         .map(|(_, name)| name.to_string())
-//         .collect();
-
-//     Ok(DataFrame { dates, data, column_names })
-// }
-
-// pub fn main(filename: &str) -> Result<DataFrame, Box<dyn Error>> {
-//     load(filename)
-// }
+use crate::arrowfile::{self, Format};
+use crate::dataframe::{CsvOptions, IndexColumn};
+use crate::error::{Error, Result};
+use crate::index::IndexFormat;
+use crate::metadata;
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub fn load(filename: &str) -> Result<DataFrame> {
+    load_with(filename, &CsvOptions::default())
+}
+
+/// Load a CSV file with a custom delimiter and index column. Parquet and Arrow IPC files
+/// are recognised by extension and only use the index column. Column tags are read from a
+/// sidecar file next to it, if there is one.
+pub fn load_with(filename: &str, options: &CsvOptions) -> Result<DataFrame> {
+    let df = match Format::from_path(filename) {
+        Some(format) => arrowfile::read(filename, format, options.index.as_ref(), "column")?,
+        None => DataFrame::new_from_csv_with(filename, options, "column")?,
+    };
+    match metadata::sidecar(filename) {
+        Some(sidecar) => Ok(metadata::attach(&df, &metadata::read(&sidecar)?)),
+        None => Ok(df),
+    }
+}
+
+pub fn main(filename: &str) -> Result<DataFrame> {
+    load(filename)
+}
+
+/// Parse a one-character delimiter such as `;` or a quoted tab
+fn parse_delimiter(delimiter: &str) -> Result<u8> {
+    match delimiter.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(Error::InvalidValue(format!("Delimiter must be a single character, got '{}'", delimiter))),
+    }
+}
+
+pub struct Load;
+
+impl Operation for Load {
+    fn name(&self) -> &str {
+        "load"
+    }
+
+    fn help(&self) -> &str {
+        "Load a CSV, Parquet or Arrow file, taking dates from the index column (a position, a header name, or auto for the first column or a columnar file's first date column) in `format` (auto, labels or e.g. %d/%m/%Y)"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("path", ArgKind::Path),
+            ArgSpec::with_default("delimiter", ArgKind::Text, ","),
+            ArgSpec::with_default("index", ArgKind::Text, "auto"),
+            ArgSpec::with_default("format", ArgKind::Text, "auto"),
+        ];
+        ARGS
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn apply(&self, _inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        let index_format: IndexFormat = args.parse(3)?;
+        let mut options = CsvOptions::default()
+            .with_delimiter(parse_delimiter(args.text(1)?)?)
+            .with_index_format(index_format);
+        if args.text(2)? != "auto" {
+            let index: IndexColumn = args.parse(2)?;
+            options = options.with_index(index);
+        }
+        load_with(args.text(0)?, &options)
+    }
+}
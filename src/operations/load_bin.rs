@@ -0,0 +1,44 @@
+use crate::binfile;
+use crate::error::Result;
+use crate::metadata;
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Memory-map a binary file, with its column tags merged with those of a sidecar file next
+/// to it, if there is one
+pub fn load_bin(filename: &str) -> Result<DataFrame> {
+    let df = binfile::read(filename)?;
+    match metadata::sidecar(filename) {
+        Some(sidecar) => Ok(metadata::attach(&df, &metadata::read(&sidecar)?)),
+        None => Ok(df),
+    }
+}
+
+pub fn main(filename: &str) -> Result<DataFrame> {
+    load_bin(filename)
+}
+
+pub struct LoadBin;
+
+impl Operation for LoadBin {
+    fn name(&self) -> &str {
+        "load_bin"
+    }
+
+    fn help(&self) -> &str {
+        "Memory-map a light-r binary file written by save_bin, with its column tags and any sidecar's"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::required("path", ArgKind::Path)];
+        ARGS
+    }
+
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn apply(&self, _inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(args.text(0)?)
+    }
+}
@@ -1,15 +1,81 @@
-// pub mod after;
-// pub mod before;
-// pub mod cgrep;
-// pub mod signal;
+pub mod after;
+pub mod before;
+pub mod cgrep;
+pub mod cross_section;
+pub mod signal;
 pub mod dlog;
-// pub mod vol_scale;
-// pub mod multiply;
-// pub mod load;
-// pub mod save;
-// pub mod ffill;
-// pub mod ewa;
-// pub mod cumsum;
-// pub mod shift;
-// pub mod plot;
-// pub mod momentum;
+pub mod vol_scale;
+pub mod multiply;
+pub mod binary;
+pub mod load;
+pub mod load_bin;
+pub mod save;
+pub mod save_bin;
+pub mod ffill;
+pub mod ewa;
+pub mod ewm;
+pub mod cumsum;
+pub mod shift;
+pub mod plot;
+pub mod momentum;
+pub mod print;
+pub mod rename;
+pub mod rolling;
+pub mod slice;
+pub mod tags;
+
+use std::sync::Arc;
+use crate::registry::Operation;
+use cross_section::Transform;
+use rolling::Stat;
+
+/// Every operation that ships with light-r
+pub fn builtins() -> Vec<Arc<dyn Operation>> {
+    vec![
+        Arc::new(load::Load),
+        Arc::new(save::Save),
+        Arc::new(load_bin::LoadBin),
+        Arc::new(save_bin::SaveBin),
+        Arc::new(print::Print),
+        Arc::new(print::Head),
+        Arc::new(plot::Plot),
+        Arc::new(after::After),
+        Arc::new(before::Before),
+        Arc::new(slice::Slice),
+        Arc::new(cgrep::Cgrep),
+        Arc::new(rename::Rename),
+        Arc::new(tags::Tags),
+        Arc::new(dlog::Dlog),
+        Arc::new(dlog::DlogMultithread),
+        Arc::new(signal::Signal),
+        Arc::new(vol_scale::UnitScale),
+        Arc::new(binary::BinaryOp::new("mult", "Multiply two frames element-wise, matching dates by join (inner, left, outer or asof), e.g. mult other outer", |a, b| a * b)),
+        Arc::new(binary::BinaryOp::new("add", "Add two frames element-wise, matching dates by join (inner, left, outer or asof), e.g. add other outer", |a, b| a + b)),
+        Arc::new(binary::BinaryOp::new("sub", "Subtract two frames element-wise, matching dates by join (inner, left, outer or asof), e.g. sub other outer", |a, b| a - b)),
+        Arc::new(binary::BinaryOp::new("div", "Divide two frames element-wise, matching dates by join (inner, left, outer or asof), e.g. div other outer", |a, b| a / b)),
+        Arc::new(ffill::Ffill),
+        Arc::new(ewa::Ewa),
+        Arc::new(cross_section::XRank),
+        Arc::new(cross_section::CrossSection::new("xzscore", "Z-score each row across columns", Transform::Zscore)),
+        Arc::new(cross_section::CrossSection::new("xdemean", "Subtract the mean of each row across columns", Transform::Demean)),
+        Arc::new(cross_section::XWinsor),
+        Arc::new(cross_section::CrossSection::new("xnormalize", "Scale each row so its absolute values sum to 1", Transform::Normalize)),
+        Arc::new(cross_section::XTopN),
+        Arc::new(ewm::Ema),
+        Arc::new(ewm::EwVol),
+        Arc::new(ewm::EwCov),
+        Arc::new(ewm::EwCorr),
+        Arc::new(cumsum::Cumsum),
+        Arc::new(shift::Shift),
+        Arc::new(momentum::Momentum),
+        Arc::new(rolling::Rolling::new("rmean", "Rolling mean over a trailing window of rows, skipping NaNs", Stat::Mean)),
+        Arc::new(rolling::Rolling::new("rstd", "Rolling sample standard deviation over a trailing window of rows", Stat::Std)),
+        Arc::new(rolling::Rolling::new("rsum", "Rolling sum over a trailing window of rows", Stat::Sum)),
+        Arc::new(rolling::Rolling::new("rmin", "Rolling minimum over a trailing window of rows", Stat::Min)),
+        Arc::new(rolling::Rolling::new("rmax", "Rolling maximum over a trailing window of rows", Stat::Max)),
+        Arc::new(rolling::Rolling::new("rmedian", "Rolling median over a trailing window of rows", Stat::Median)),
+        Arc::new(rolling::RollingQuantile),
+        Arc::new(rolling::Rolling::new("rskew", "Rolling bias-corrected skewness over a trailing window of rows", Stat::Skew)),
+        Arc::new(rolling::Rolling::new("rzscore", "Z-score of each value against its trailing window of rows", Stat::Zscore)),
+    ]
+}
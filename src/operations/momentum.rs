@@ -1,41 +1,47 @@
-// use crate::DataFrame;
-// use std::error::Error;
-
-// pub fn calculate_momentum(df: &DataFrame, lookback_period: usize, frequency: usize) -> Result<DataFrame, Box<dyn Error>> {
-//     if df.data.is_empty() {
-//         return Err("No data available for momentum calculation.".into());
-//     }
-
-//     let frequency = frequency.max(1);
-//     let mut momentum_data = Vec::new();
-//     let mut momentum_dates = Vec::new();
-
-//     for i in (lookback_period..df.data.len()).step_by(frequency) {
-//         let mut row = Vec::with_capacity(df.data[0].len());
-//         for j in 0..df.data[0].len() {
-//             let current = df.data[i][j];
-//             let previous = df.data[i - lookback_period][j];
-//             let momentum = if previous != 0.0 {
-//                 (current - previous) / previous
-//             } else {
-//                 f64::NAN
-//             };
-//             row.push(momentum);
-//         }
-//         momentum_data.push(row);
-//         momentum_dates.push(df.dates[i]);
-//     }
-
-//     Ok(DataFrame {
-//         dates: momentum_dates,
-//         data: momentum_data,
-//         column_names: df.column_names.clone(),
-//     })
-// }
-
-// pub fn main(df: &DataFrame, lookback: usize, frequency: usize) -> Result<DataFrame, Box<dyn Error>> {
-//     if df.data.is_empty() {
-//         return Err("No data available for momentum calculation.".into());
-//     }
-//     calculate_momentum(df, lookback, frequency)
-// }
+use crate::error::{Error, Result};
+use crate::{DataFrame, Engine};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub fn calculate_momentum(df: &DataFrame, lookback_period: usize, frequency: usize) -> Result<DataFrame> {
+    if df.num_rows == 0 {
+        return Err(Error::NoInput("No data available for momentum calculation".to_string()));
+    }
+
+    let frequency = frequency.max(1);
+    let sampled_rows: Vec<usize> = (lookback_period..df.num_rows).step_by(frequency).collect();
+
+    Engine::parallel_process_into(df, df.index.select(&sampled_rows), |values, momentum| {
+        for (out, &row) in momentum.iter_mut().zip(&sampled_rows) {
+            let (current, previous) = (values[row], values[row - lookback_period]);
+            *out = if previous != 0.0 { (current - previous) / previous } else { f64::NAN };
+        }
+    })
+}
+
+pub fn main(df: &DataFrame, lookback: usize, frequency: usize) -> Result<DataFrame> {
+    calculate_momentum(df, lookback, frequency)
+}
+
+pub struct Momentum;
+
+impl Operation for Momentum {
+    fn name(&self) -> &str {
+        "momentum"
+    }
+
+    fn help(&self) -> &str {
+        "Return over a lookback, sampled every `frequency` rows"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("lookback", ArgKind::Count),
+            ArgSpec::with_default("frequency", ArgKind::Count, "1"),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.parse(0)?, args.parse(1)?)
+    }
+}
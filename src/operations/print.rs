@@ -0,0 +1,42 @@
+use crate::error::Result;
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub struct Print;
+
+impl Operation for Print {
+    fn name(&self) -> &str {
+        "print"
+    }
+
+    fn help(&self) -> &str {
+        "Print the current frame as CSV and pass it through"
+    }
+
+    fn apply(&self, inputs: &[DataFrame], _args: &Args) -> Result<DataFrame> {
+        inputs[0].print();
+        Ok(inputs[0].clone())
+    }
+}
+
+pub struct Head;
+
+impl Operation for Head {
+    fn name(&self) -> &str {
+        "head"
+    }
+
+    fn help(&self) -> &str {
+        "Print the first rows of the current frame and pass it through"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::with_default("rows", ArgKind::Count, "10")];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        inputs[0].print_head(args.parse(0)?);
+        Ok(inputs[0].clone())
+    }
+}
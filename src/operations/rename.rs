@@ -0,0 +1,52 @@
+use crate::error::{Error, Result};
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Rename columns given as (old, new) pairs. Values and tags are untouched and shared with
+/// the input.
+pub fn rename(df: &DataFrame, renames: &[(String, String)]) -> Result<DataFrame> {
+    let mut column_names = df.column_names.clone();
+    for (old, new) in renames {
+        let position = df
+            .column_names
+            .iter()
+            .position(|name| name == old)
+            .ok_or_else(|| Error::MissingColumn(old.clone()))?;
+        column_names[position] = new.clone();
+    }
+    for (i, name) in column_names.iter().enumerate() {
+        if column_names[..i].contains(name) {
+            return Err(Error::InvalidValue(format!("Renaming would leave two columns named {}", name)));
+        }
+    }
+    Ok(DataFrame { column_names, ..df.clone() })
+}
+
+pub fn main(df: &DataFrame, names: &[String]) -> Result<DataFrame> {
+    if !names.len().is_multiple_of(2) {
+        return Err(Error::InvalidValue("rename takes pairs of old and new column names".to_string()));
+    }
+    let renames: Vec<(String, String)> = names.chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect();
+    rename(df, &renames)
+}
+
+pub struct Rename;
+
+impl Operation for Rename {
+    fn name(&self) -> &str {
+        "rename"
+    }
+
+    fn help(&self) -> &str {
+        "Rename columns, given as pairs of old and new names"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::variadic("names", ArgKind::Text)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.rest(0))
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::VecDeque;
+
+use crate::error::{Error, Result};
+use crate::{DataFrame, Engine};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+// Statistics over a trailing window of rows, as pandas' `rolling(window, min_periods)`. NaNs
+// take up a row of the window but are left out of the statistic, which is NaN until the
+// window holds `min_periods` values. Each kernel updates its state as one value enters and
+// one leaves the window, so a column costs O(n) whatever the window size, or O(n log n) for
+// the median and quantiles. Sums are compensated and keep infinities apart, so a value
+// leaving the window takes its contribution with it. The moments behind std, skew and
+// z-score are merged from summaries rather than updated by subtraction, so removing values
+// cancels no precision.
+
+/// A statistic over a window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stat {
+    Mean,
+    Std,                                // Sample standard deviation
+    Sum,
+    Min,
+    Max,
+    Median,
+    Quantile(f64),                      // Linearly interpolated, between 0 and 1
+    Skew,                               // Bias-corrected sample skewness
+    Zscore,                             // Distance of the last value from the mean, in standard deviations
+}
+
+impl Stat {
+    /// Fewest values the statistic is defined for
+    fn min_count(self) -> usize {
+        match self {
+            Stat::Std | Stat::Zscore => 2,
+            Stat::Skew => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// A trailing window of `size` rows, of which at least `min_periods` must hold values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub size: usize,
+    pub min_periods: usize,
+}
+
+impl Window {
+    /// A window of `size` rows; `min_periods` defaults to the full window
+    pub fn new(size: usize, min_periods: Option<usize>) -> Result<Self> {
+        let min_periods = min_periods.unwrap_or(size);
+        if size == 0 {
+            return Err(Error::InvalidValue("Window size must be greater than zero".to_string()));
+        }
+        if min_periods == 0 || min_periods > size {
+            return Err(Error::InvalidValue(format!("min_periods must be between 1 and the window size {}, got {}", size, min_periods)));
+        }
+        Ok(Window { size, min_periods })
+    }
+}
+
+/// State of a statistic as values enter and leave the window, oldest first. NaNs are never
+/// passed in.
+trait Kernel {
+    fn add(&mut self, row: usize, x: f64);
+    fn remove(&mut self, row: usize, x: f64);
+    /// The statistic over the `count` values in the window, whose last row holds `current`
+    fn value(&self, count: usize, current: f64) -> f64;
+}
+
+/// Neumaier-compensated running sum, so removing values doesn't accumulate rounding error.
+/// Infinities are counted instead of summed, since inf - inf would poison the sum for good.
+#[derive(Default)]
+struct Summed {
+    sum: f64,
+    compensation: f64,
+    infinities: [usize; 2],             // Counts of -inf and +inf in the window
+    mean: bool,                         // Divide by the count
+}
+
+impl Summed {
+    /// Add `x` to the window, or remove it if `add` is false
+    fn update(&mut self, x: f64, add: bool) {
+        if x.is_infinite() {
+            let count = &mut self.infinities[usize::from(x > 0.0)];
+            if add { *count += 1 } else { *count -= 1 }
+        } else {
+            self.accumulate(if add { x } else { -x });
+        }
+    }
+
+    fn accumulate(&mut self, x: f64) {
+        let total = self.sum + x;
+        self.compensation += if self.sum.abs() >= x.abs() { (self.sum - total) + x } else { (x - total) + self.sum };
+        self.sum = total;
+    }
+}
+
+impl Kernel for Summed {
+    fn add(&mut self, _row: usize, x: f64) {
+        self.update(x, true);
+    }
+
+    fn remove(&mut self, _row: usize, x: f64) {
+        self.update(x, false);
+    }
+
+    fn value(&self, count: usize, _current: f64) -> f64 {
+        let sum = match self.infinities {
+            [0, 0] => self.sum + self.compensation,
+            [0, _] => f64::INFINITY,
+            [_, 0] => f64::NEG_INFINITY,
+            _ => f64::NAN,
+        };
+        if self.mean { sum / count as f64 } else { sum }
+    }
+}
+
+/// Count, mean and sums of squared and cubed deviations from the mean of some values
+#[derive(Debug, Clone, Copy, Default)]
+struct Summary {
+    n: f64,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+}
+
+impl Summary {
+    fn of(x: f64) -> Self {
+        Summary { n: 1.0, mean: x, m2: 0.0, m3: 0.0 }
+    }
+
+    /// The summary of both sets of values (Chan, Golub and LeVeque). Equal means add no
+    /// deviation, so constant windows stay exact.
+    fn merge(self, other: Summary) -> Summary {
+        if self.n == 0.0 {
+            return other;
+        }
+        if other.n == 0.0 {
+            return self;
+        }
+        let (na, nb) = (self.n, other.n);
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        Summary {
+            n,
+            mean: self.mean + delta * nb / n,
+            m2: self.m2 + other.m2 + delta * delta * na * nb / n,
+            m3: self.m3 + other.m3 + delta.powi(3) * na * nb * (na - nb) / (n * n) + 3.0 * delta * (na * other.m2 - nb * self.m2) / n,
+        }
+    }
+}
+
+/// Moments of the window as a queue of two stacks: values enter the back, which keeps their
+/// running summary, and leave the front, which holds the summary of each value and those
+/// after it. When the front runs out the back is moved over, so each value is merged a
+/// constant number of times.
+#[derive(Default)]
+struct Moments {
+    front: Vec<Summary>,                // Oldest value last, each entry summarising itself and newer front values
+    back: Vec<f64>,                     // Newest value last
+    back_summary: Summary,
+    stat: Option<Stat>,
+}
+
+impl Kernel for Moments {
+    fn add(&mut self, _row: usize, x: f64) {
+        self.back.push(x);
+        self.back_summary = self.back_summary.merge(Summary::of(x));
+    }
+
+    fn remove(&mut self, _row: usize, _x: f64) {
+        if self.front.is_empty() {
+            let mut newer = Summary::default();
+            for &x in self.back.iter().rev() {
+                newer = Summary::of(x).merge(newer);
+                self.front.push(newer);
+            }
+            self.back.clear();
+            self.back_summary = Summary::default();
+        }
+        self.front.pop();
+    }
+
+    fn value(&self, _count: usize, current: f64) -> f64 {
+        let window = self.front.last().copied().unwrap_or_default().merge(self.back_summary);
+        let n = window.n;
+        let variance = window.m2 / (n - 1.0);
+        match self.stat {
+            Some(Stat::Std) => variance.sqrt(),
+            Some(Stat::Zscore) if variance > 0.0 => (current - window.mean) / variance.sqrt(),
+            Some(Stat::Skew) => {
+                let (b, c) = (window.m2 / n, window.m3 / n);
+                if variance == 0.0 {
+                    return f64::NAN;
+                }
+                (n * (n - 1.0)).sqrt() / (n - 2.0) * c / (b * b.sqrt())
+            }
+            _ => f64::NAN,
+        }
+    }
+}
+
+/// Running minimum or maximum: candidate rows in a deque, each better than those before it
+struct Extreme {
+    rows: VecDeque<(usize, f64)>,
+    max: bool,
+}
+
+impl Kernel for Extreme {
+    fn add(&mut self, row: usize, x: f64) {
+        while let Some(&(_, last)) = self.rows.back() {
+            if (self.max && last <= x) || (!self.max && last >= x) {
+                self.rows.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.rows.push_back((row, x));
+    }
+
+    fn remove(&mut self, row: usize, _x: f64) {
+        if self.rows.front().is_some_and(|&(first, _)| first == row) {
+            self.rows.pop_front();
+        }
+    }
+
+    fn value(&self, _count: usize, _current: f64) -> f64 {
+        self.rows.front().map_or(f64::NAN, |&(_, x)| x)
+    }
+}
+
+/// Counts of the window's values by their rank in the whole column, in a Fenwick tree, so
+/// the median and quantiles are found by rank in O(log n)
+struct Ordered {
+    sorted: Vec<f64>,                   // The column's values in order, NaNs left out
+    ranks: Vec<usize>,                  // Position of each row's value in `sorted`
+    counts: Vec<usize>,                 // Fenwick tree over ranks, from 1
+    quantile: f64,
+}
+
+impl Ordered {
+    fn new(values: &[f64], quantile: f64) -> Self {
+        let mut rows: Vec<usize> = (0..values.len()).filter(|&row| !values[row].is_nan()).collect();
+        rows.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let mut ranks = vec![0; values.len()];
+        for (rank, &row) in rows.iter().enumerate() {
+            ranks[row] = rank;
+        }
+        let sorted = rows.iter().map(|&row| values[row]).collect();
+        Ordered { sorted, ranks, counts: vec![0; rows.len() + 1], quantile }
+    }
+
+    fn update(&mut self, row: usize, add: bool) {
+        let mut i = self.ranks[row] + 1;
+        while i < self.counts.len() {
+            if add { self.counts[i] += 1 } else { self.counts[i] -= 1 }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The `k`-th smallest value in the window, from 0
+    fn nth(&self, k: usize) -> f64 {
+        // Descend to the last rank with at most k values at or below it
+        let (mut position, mut remaining) = (0, k);
+        let mut step = self.counts.len().next_power_of_two() / 2;
+        while step > 0 {
+            let next = position + step;
+            if next < self.counts.len() && self.counts[next] <= remaining {
+                position = next;
+                remaining -= self.counts[next];
+            }
+            step /= 2;
+        }
+        self.sorted[position]
+    }
+}
+
+impl Kernel for Ordered {
+    fn add(&mut self, row: usize, _x: f64) {
+        self.update(row, true);
+    }
+
+    fn remove(&mut self, row: usize, _x: f64) {
+        self.update(row, false);
+    }
+
+    fn value(&self, count: usize, _current: f64) -> f64 {
+        let position = self.quantile * (count - 1) as f64;
+        let (below, fraction) = (position.floor() as usize, position.fract());
+        let low = self.nth(below);
+        if fraction > 0.0 && below + 1 < count {
+            low + (self.nth(below + 1) - low) * fraction
+        } else {
+            low
+        }
+    }
+}
+
+fn run(mut kernel: impl Kernel, values: &[f64], out: &mut [f64], window: Window, min_count: usize) {
+    let needed = window.min_periods.max(min_count);
+    let mut count = 0;
+    for (row, (&x, out)) in values.iter().zip(out.iter_mut()).enumerate() {
+        if !x.is_nan() {
+            kernel.add(row, x);
+            count += 1;
+        }
+        if let Some(old) = row.checked_sub(window.size) {
+            if !values[old].is_nan() {
+                kernel.remove(old, values[old]);
+                count -= 1;
+            }
+        }
+        *out = if count >= needed { kernel.value(count, x) } else { f64::NAN };
+    }
+}
+
+/// Compute `stat` over the trailing window ending at each value of one column
+pub fn rolling_column(values: &[f64], out: &mut [f64], stat: Stat, window: Window) {
+    let min_count = stat.min_count();
+    match stat {
+        Stat::Mean | Stat::Sum => run(Summed { mean: stat == Stat::Mean, ..Summed::default() }, values, out, window, min_count),
+        Stat::Std | Stat::Skew | Stat::Zscore => run(Moments { stat: Some(stat), ..Moments::default() }, values, out, window, min_count),
+        Stat::Min | Stat::Max => run(Extreme { rows: VecDeque::with_capacity(window.size), max: stat == Stat::Max }, values, out, window, min_count),
+        Stat::Median => run(Ordered::new(values, 0.5), values, out, window, min_count),
+        Stat::Quantile(quantile) => run(Ordered::new(values, quantile), values, out, window, min_count),
+    }
+}
+
+/// Compute `stat` over a trailing window of every column, one column per worker
+pub fn rolling(df: &DataFrame, stat: Stat, window: Window) -> Result<DataFrame> {
+    if let Stat::Quantile(q) = stat {
+        if !(0.0..=1.0).contains(&q) {
+            return Err(Error::InvalidValue(format!("Quantile must be between 0 and 1, got {}", q)));
+        }
+    }
+    Engine::parallel_process(df, |values, out| rolling_column(values, out, stat, window))
+}
+
+pub fn main(df: &DataFrame, stat: Stat, size: usize, min_periods: Option<usize>) -> Result<DataFrame> {
+    rolling(df, stat, Window::new(size, min_periods)?)
+}
+
+/// The `min_periods` argument at `index`, if given
+fn min_periods(args: &Args, index: usize) -> Result<Option<usize>> {
+    args.get(index).map(|_| args.parse(index)).transpose()
+}
+
+/// A rolling statistic registered under its own verb, such as `rmean`
+pub struct Rolling {
+    name: &'static str,
+    help: &'static str,
+    stat: Stat,
+}
+
+impl Rolling {
+    pub const fn new(name: &'static str, help: &'static str, stat: Stat) -> Self {
+        Rolling { name, help, stat }
+    }
+}
+
+impl Operation for Rolling {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn help(&self) -> &str {
+        self.help
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("window", ArgKind::Count),
+            ArgSpec::optional("min_periods", ArgKind::Count),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], self.stat, args.parse(0)?, min_periods(args, 1)?)
+    }
+}
+
+pub struct RollingQuantile;
+
+impl Operation for RollingQuantile {
+    fn name(&self) -> &str {
+        "rquantile"
+    }
+
+    fn help(&self) -> &str {
+        "Rolling quantile over a trailing window of rows, interpolated linearly"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[
+            ArgSpec::required("window", ArgKind::Count),
+            ArgSpec::required("q", ArgKind::Number),
+            ArgSpec::optional("min_periods", ArgKind::Count),
+        ];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], Stat::Quantile(args.parse(1)?), args.parse(0)?, min_periods(args, 2)?)
+    }
+}
@@ -1,27 +1,56 @@
-// use crate::DataFrame;
-// use std::error::Error;
-// use std::fs::File;
-// use std::io::Write;
-
-// pub fn save(df: &DataFrame, filename: &str) -> Result<(), Box<dyn Error>> {
-//     let mut file = File::create(filename)?;
-
-//     // Write header
-//     writeln!(file, "DATE,{}", df.column_names.join(","))?;
-
-//     // Write data
-//     for (date, row) in df.dates.iter().zip(df.data.iter()) {
-//         write!(file, "{}", date.format("%Y-%m-%d"))?;
-//         for value in row {
-//             write!(file, ",{}", value)?;
-//         }
-//         writeln!(file)?;
-//     }
-
-//     Ok(())
-// }
-
-// pub fn main(df: &DataFrame, filename: &str) -> Result<DataFrame, Box<dyn Error>> {
-//     save(df, filename)?;
-//     Ok(df.clone())
-// }
+use crate::arrowfile::{self, Format};
+use crate::error::Result;
+use crate::DataFrame;
+use std::fs::File;
+use std::io::BufWriter;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Write a frame as CSV, or as Parquet or Arrow IPC for those file extensions
+pub fn save(df: &DataFrame, filename: &str) -> Result<()> {
+    if let Some(format) = Format::from_path(filename) {
+        return arrowfile::write(df, filename, format);
+    }
+    // Names and labels are quoted where they hold a comma or a quote
+    let mut wtr = csv::Writer::from_writer(BufWriter::new(File::create(filename)?));
+
+    // Write header
+    wtr.write_record(std::iter::once("DATE").chain(df.column_names.iter().map(String::as_str)))?;
+
+    // Write data
+    let mut record = Vec::with_capacity(df.num_columns + 1);
+    for row in 0..df.num_rows {
+        record.clear();
+        record.push(df.index.label(row));
+        record.extend(df.row(row).iter().map(|value| value.to_string()));
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn main(df: &DataFrame, filename: &str) -> Result<DataFrame> {
+    save(df, filename)?;
+    Ok(df.clone())
+}
+
+pub struct Save;
+
+impl Operation for Save {
+    fn name(&self) -> &str {
+        "save"
+    }
+
+    fn help(&self) -> &str {
+        "Write the current frame to a CSV, Parquet or Arrow file and pass it through"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::required("path", ArgKind::Path)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.text(0)?)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::binfile;
+use crate::error::Result;
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+pub fn save_bin(df: &DataFrame, filename: &str) -> Result<()> {
+    binfile::write(df, filename)
+}
+
+pub fn main(df: &DataFrame, filename: &str) -> Result<DataFrame> {
+    save_bin(df, filename)?;
+    Ok(df.clone())
+}
+
+pub struct SaveBin;
+
+impl Operation for SaveBin {
+    fn name(&self) -> &str {
+        "save_bin"
+    }
+
+    fn help(&self) -> &str {
+        "Write the current frame and its column tags to a light-r binary file and pass it through"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::required("path", ArgKind::Path)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.text(0)?)
+    }
+}
@@ -1,31 +1,42 @@
-// use crate::DataFrame;
-// use std::error::Error;
+use crate::error::Result;
+use crate::{DataFrame, Engine};
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
 
-// pub fn shift(df: &DataFrame, shift_by: i32) -> Result<DataFrame, Box<dyn Error>> {
-//     let mut shifted_data = Vec::with_capacity(df.data.len());
-//     let num_columns = df.data[0].len();
+pub fn shift(df: &DataFrame, shift_by: i32) -> Result<DataFrame> {
+    // Shift down for positive periods, up for negative ones
+    let distance = (shift_by.unsigned_abs() as usize).min(df.num_rows);
+    let kept = df.num_rows - distance;
+    Engine::parallel_process(df, |values, shifted| {
+        shifted.fill(f64::NAN);
+        if shift_by >= 0 {
+            shifted[distance..].copy_from_slice(&values[..kept]);
+        } else {
+            shifted[..kept].copy_from_slice(&values[distance..]);
+        }
+    })
+}
 
-//     if shift_by >= 0 {
-//         // Shift down
-//         for _ in 0..shift_by {
-//             shifted_data.push(vec![f64::NAN; num_columns]);
-//         }
-//         shifted_data.extend_from_slice(&df.data[..(df.data.len() - shift_by as usize)]);
-//     } else {
-//         // Shift up
-//         shifted_data.extend_from_slice(&df.data[(-shift_by as usize)..]);
-//         for _ in 0..(-shift_by) {
-//             shifted_data.push(vec![f64::NAN; num_columns]);
-//         }
-//     }
+pub fn main(df: &DataFrame, shift_by: i32) -> Result<DataFrame> {
+    shift(df, shift_by)
+}
 
-//     Ok(DataFrame {
-//         dates: df.dates.clone(),
-//         data: shifted_data,
-//         column_names: df.column_names.clone(),
-//     })
-// }
+pub struct Shift;
 
-// pub fn main(df: &DataFrame, shift_by: i32) -> Result<DataFrame, Box<dyn Error>> {
-//     shift(df, shift_by)
-// }
+impl Operation for Shift {
+    fn name(&self) -> &str {
+        "shift"
+    }
+
+    fn help(&self) -> &str {
+        "Shift rows down by a number of periods (up if negative)"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::with_default("periods", ArgKind::Integer, "1")];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.parse(0)?)
+    }
+}
@@ -1,23 +1,31 @@
-// use crate::DataFrame;
-// use std::error::Error;
+use crate::error::Result;
+use crate::{DataFrame, Engine};
+use crate::registry::{Args, Operation};
 
-// pub fn calculate_signal(df: &DataFrame) -> Result<DataFrame, Box<dyn Error>> {
-//     let mut signal_data = Vec::with_capacity(df.data.len());
+pub fn calculate_signal(df: &DataFrame) -> Result<DataFrame> {
+    Engine::parallel_process(df, |values, signals| {
+        for (out, &x) in signals.iter_mut().zip(values) {
+            *out = if x > 0.0 { 1.0 } else { -1.0 };
+        }
+    })
+}
 
-//     for row in &df.data {
-//         let signal_row: Vec<f64> = row.iter()
-//             .map(|&x| if x > 0.0 { 1.0 } else { -1.0 })
-//             .collect();
-//         signal_data.push(signal_row);
-//     }
+pub fn main(df: &DataFrame) -> Result<DataFrame> {
+    calculate_signal(df)
+}
 
-//     Ok(DataFrame {
-//         dates: df.dates.clone(),
-//         data: signal_data,
-//         column_names: df.column_names.clone(),
-//     })
-// }
+pub struct Signal;
 
-// pub fn main(df: &DataFrame) -> Result<DataFrame, Box<dyn Error>> {
-//     calculate_signal(df)
-// }
+impl Operation for Signal {
+    fn name(&self) -> &str {
+        "signal"
+    }
+
+    fn help(&self) -> &str {
+        "Map positive values to 1 and everything else to -1"
+    }
+
+    fn apply(&self, inputs: &[DataFrame], _args: &Args) -> Result<DataFrame> {
+        main(&inputs[0])
+    }
+}
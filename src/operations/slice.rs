@@ -0,0 +1,133 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime};
+use crate::error::{Error, Result};
+use crate::index::Index;
+use crate::DataFrame;
+use crate::operations::after::parse_date;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Which rows `slice` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selector {
+    After(NaiveDate),                   // Strictly after the date
+    Before(NaiveDate),                  // Strictly before the date
+    Between(NaiveDate, NaiveDate),      // From the first date to the second, inclusive
+    LastMonths(u32),                    // `last 3y`, `last 6m`: the period ending at the last row
+    LastDays(u32),                      // `last 2w`, `last 10d`
+    LastRows(usize),                    // `last 250`
+    Year(i32),                          // `2008`
+    Month(i32, u32),                    // `2020-03`
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::InvalidValue(format!(
+                "Invalid slice '{}': expected after DATE, before DATE, between DATE DATE, last N[y|m|w|d], YYYY or YYYY-MM",
+                s
+            ))
+        };
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words[..] {
+            ["after", date] => Ok(Selector::After(parse_date(date)?)),
+            ["before", date] => Ok(Selector::Before(parse_date(date)?)),
+            ["between", from, to] => Ok(Selector::Between(parse_date(from)?, parse_date(to)?)),
+            ["last", span] => {
+                let digits = span.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                let count = digits.parse::<u32>().map_err(|_| invalid())?;
+                match &span[digits.len()..] {
+                    "" => Ok(Selector::LastRows(count as usize)),
+                    "y" => Ok(Selector::LastMonths(count.checked_mul(12).ok_or_else(invalid)?)),
+                    "m" => Ok(Selector::LastMonths(count)),
+                    "w" => Ok(Selector::LastDays(count.checked_mul(7).ok_or_else(invalid)?)),
+                    "d" => Ok(Selector::LastDays(count)),
+                    _ => Err(invalid()),
+                }
+            }
+            [period] => {
+                let mut parts = period.splitn(2, '-');
+                let year = parts.next().filter(|year| year.len() == 4).and_then(|year| year.parse().ok()).ok_or_else(invalid)?;
+                match parts.next() {
+                    None => Ok(Selector::Year(year)),
+                    Some(month) => {
+                        let month = month.parse().ok().filter(|month| (1..=12).contains(month)).ok_or_else(invalid)?;
+                        Ok(Selector::Month(year, month))
+                    }
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Selector {
+    /// The range of rows selected from an increasing index, found by binary search.
+    /// Only `LastRows` works on an index of labels.
+    pub fn rows(&self, index: &Index) -> Result<Range<usize>> {
+        let n = index.len();
+        let range = match *self {
+            Selector::After(date) => index.partition_point(|time| time.date() <= date)?..n,
+            Selector::Before(date) => 0..index.partition_point(|time| time.date() < date)?,
+            Selector::Between(from, to) => {
+                index.partition_point(|time| time.date() < from)?..index.partition_point(|time| time.date() <= to)?
+            }
+            Selector::Year(year) => {
+                index.partition_point(|time| time.year() < year)?..index.partition_point(|time| time.year() <= year)?
+            }
+            Selector::Month(year, month) => {
+                let key = |time: NaiveDateTime| (time.year(), time.month());
+                index.partition_point(|time| key(time) < (year, month))?..index.partition_point(|time| key(time) <= (year, month))?
+            }
+            Selector::LastRows(count) => n.saturating_sub(count)..n,
+            Selector::LastMonths(months) => since(index, |last| last.checked_sub_months(Months::new(months)))?,
+            Selector::LastDays(days) => since(index, |last| last.checked_sub_signed(Duration::days(days.into())))?,
+        };
+        // A reversed `between` selects nothing
+        Ok(range.start..range.end.max(range.start))
+    }
+}
+
+/// The rows after the cutoff that `period` gives from the last row's time
+fn since(index: &Index, period: impl Fn(NaiveDateTime) -> Option<NaiveDateTime>) -> Result<Range<usize>> {
+    let n = index.len();
+    if n == 0 {
+        return Ok(0..0);
+    }
+    let last = index.time(n - 1).ok_or_else(|| Error::InvalidValue("The row index holds labels, not dates".to_string()))?;
+    let cutoff = period(last).ok_or_else(|| Error::InvalidValue("Slice period reaches out of the supported date range".to_string()))?;
+    Ok(index.partition_point(|time| time <= cutoff)?..n)
+}
+
+/// The selected rows as a view sharing the input's mapping
+pub fn slice(df: &DataFrame, selector: &Selector) -> Result<DataFrame> {
+    Ok(df.slice_rows(selector.rows(&df.index)?))
+}
+
+pub fn main(df: &DataFrame, selector: &str) -> Result<DataFrame> {
+    slice(df, &selector.parse()?)
+}
+
+pub struct Slice;
+
+impl Operation for Slice {
+    fn name(&self) -> &str {
+        "slice"
+    }
+
+    fn help(&self) -> &str {
+        "Keep a range of rows: after DATE, before DATE, between DATE DATE (inclusive), last 3y, 6m, 2w, 10d or 250 (rows), a year such as 2008 or a month such as 2020-03"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::variadic("selector", ArgKind::Text)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], &args.rest(0).join(" "))
+    }
+}
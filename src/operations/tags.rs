@@ -0,0 +1,65 @@
+use std::collections::BTreeSet;
+
+use crate::error::Result;
+use crate::metadata;
+use crate::DataFrame;
+use crate::registry::{ArgKind, ArgSpec, Args, Operation};
+
+/// Attach tags from a CSV or JSON file, keyed by column name
+pub fn attach_file(df: &DataFrame, path: &str) -> Result<DataFrame> {
+    Ok(metadata::attach(df, &metadata::read(path)?))
+}
+
+/// Print the tags as CSV: one row per column, one field per tag key
+pub fn print_tags(df: &DataFrame) {
+    let keys: BTreeSet<&String> = df.tags.iter().flat_map(|tags| tags.keys()).collect();
+    let mut wtr = csv::Writer::from_writer(std::io::stdout());
+    let mut header = vec!["column"];
+    header.extend(keys.iter().map(|key| key.as_str()));
+    if let Err(err) = wtr.write_record(&header) {
+        eprintln!("Error writing header: {}", err);
+        return;
+    }
+    for (name, tags) in df.column_names.iter().zip(&df.tags) {
+        let mut record = vec![name.as_str()];
+        record.extend(keys.iter().map(|&key| tags.get(key).map_or("", String::as_str)));
+        if let Err(err) = wtr.write_record(&record) {
+            eprintln!("Error writing tags of {}: {}", name, err);
+            return;
+        }
+    }
+    if let Err(err) = wtr.flush() {
+        eprintln!("Error flushing CSV writer: {}", err);
+    }
+}
+
+pub fn main(df: &DataFrame, path: Option<&str>) -> Result<DataFrame> {
+    match path {
+        Some(path) => attach_file(df, path),
+        None => {
+            print_tags(df);
+            Ok(df.clone())
+        }
+    }
+}
+
+pub struct Tags;
+
+impl Operation for Tags {
+    fn name(&self) -> &str {
+        "tags"
+    }
+
+    fn help(&self) -> &str {
+        "Attach column tags from a CSV or JSON file keyed by column name, or print the tags when no file is given"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::optional("path", ArgKind::Path)];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        main(&inputs[0], args.get(0))
+    }
+}
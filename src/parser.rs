@@ -0,0 +1,264 @@
+use std::fmt;
+
+/// Position of a token in the command string (1-based)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A single argument to a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Word(String),           // Bare word, e.g. a file path, date or column name
+    Str(String),            // Quoted string with escapes resolved
+    Number(f64, String),    // Numeric literal and its source text, e.g. `007`
+    Var(String),            // Variable reference written as `$name`
+}
+
+impl Arg {
+    /// The argument as the engine sees it: numbers keep the text they were written with and
+    /// variable references resolve to their name
+    pub fn text(&self) -> String {
+        match self {
+            Arg::Word(s) | Arg::Str(s) | Arg::Number(_, s) | Arg::Var(s) => s.clone(),
+        }
+    }
+}
+
+/// One stage of a pipeline: a command name and its arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub name: String,
+    pub args: Vec<Arg>,
+    pub stage: usize,   // 1-based index of the stage within its pipeline
+    pub span: Span,     // Position of the command name
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for arg in &self.args {
+            match arg {
+                Arg::Str(s) => write!(f, " {:?}", s)?,
+                Arg::Var(s) => write!(f, " ${}", s)?,
+                _ => write!(f, " {}", arg.text())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `|`-separated sequence of commands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub stage: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {} (stage {}): {}", self.line, self.column, self.stage, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Show the offending source line with a caret under the error column
+    pub fn render(&self, source: &str) -> String {
+        let line = source.lines().nth(self.line - 1).unwrap_or("");
+        format!("{}\n{}\n{}^", self, line, " ".repeat(self.column - 1))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Pipe,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    stage: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str, line: usize) -> Self {
+        Lexer { chars: input.chars().peekable(), line, column: 1, stage: 1 }
+    }
+
+    fn span(&self) -> Span {
+        Span { line: self.line, column: self.column }
+    }
+
+    fn error(&self, span: Span, message: impl Into<String>) -> ParseError {
+        lexer_error(span, self.stage, message)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Resolve the character following a backslash
+    fn escape(&mut self, start: Span) -> Result<char, ParseError> {
+        match self.bump() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some(c @ ('\\' | '"' | '\'' | '|' | ' ' | '$' | '#')) => Ok(c),
+            Some(c) => Err(self.error(start, format!("unknown escape sequence '\\{}'", c))),
+            None => Err(self.error(start, "unterminated escape sequence")),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, Span)>, ParseError> {
+        loop {
+            while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+                self.bump();
+            }
+            // A `#` at the start of a token comments out the rest of the line
+            if self.chars.peek() != Some(&'#') {
+                break;
+            }
+            while self.chars.peek().is_some_and(|&c| c != '\n') {
+                self.bump();
+            }
+        }
+
+        let start = self.span();
+        let Some(&c) = self.chars.peek() else { return Ok(None) };
+
+        if c == '|' {
+            self.bump();
+            self.stage += 1;
+            return Ok(Some((Token::Pipe, start)));
+        }
+
+        if c == '"' || c == '\'' {
+            self.bump();
+            let mut value = String::new();
+            loop {
+                let span = self.span();
+                match self.bump() {
+                    Some(q) if q == c => break,
+                    // Single quotes are literal, double quotes process escapes
+                    Some('\\') if c == '"' => value.push(self.escape(span)?),
+                    Some(ch) => value.push(ch),
+                    None => return Err(self.error(start, "unterminated quoted string")),
+                }
+            }
+            return Ok(Some((Token::Quoted(value), start)));
+        }
+
+        let mut value = String::new();
+        while let Some(&ch) = self.chars.peek() {
+            if ch.is_whitespace() || ch == '|' {
+                break;
+            }
+            if ch == '"' || ch == '\'' {
+                return Err(self.error(self.span(), "unexpected quote inside a word"));
+            }
+            let span = self.span();
+            self.bump();
+            if ch == '\\' {
+                value.push(self.escape(span)?);
+            } else {
+                value.push(ch);
+            }
+        }
+        Ok(Some((Token::Word(value), start)))
+    }
+}
+
+fn classify(word: String) -> Arg {
+    if let Some(name) = word.strip_prefix('$') {
+        return Arg::Var(name.to_string());
+    }
+    match word.parse::<f64>() {
+        // Reject words like "inf" or "nan" so they stay plain words
+        Ok(n) if n.is_finite() => Arg::Number(n, word),
+        _ => Arg::Word(word),
+    }
+}
+
+/// Parse a pipeline such as `load data.csv | after 2020-01-01 | -> px`
+pub fn parse_pipeline(input: &str) -> Result<Pipeline, ParseError> {
+    parse_line(input, 1)?.ok_or_else(|| {
+        let end = Lexer::new(input, 1).span();
+        lexer_error(end, 1, "empty pipeline")
+    })
+}
+
+/// Parse a script with one pipeline per line; blank lines and `#` comments are skipped
+pub fn parse_script(input: &str) -> Result<Vec<Pipeline>, ParseError> {
+    let mut pipelines = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if let Some(pipeline) = parse_line(line, index + 1)? {
+            pipelines.push(pipeline);
+        }
+    }
+    Ok(pipelines)
+}
+
+/// Parse one pipeline starting at the given line number, or `None` if it holds no commands
+fn parse_line(input: &str, line: usize) -> Result<Option<Pipeline>, ParseError> {
+    let mut lexer = Lexer::new(input, line);
+    let mut commands = Vec::new();
+    let mut current: Option<Command> = None;
+
+    loop {
+        let stage = lexer.stage;
+        match lexer.next_token()? {
+            Some((Token::Pipe, span)) => {
+                let command = current.take().ok_or_else(|| lexer_error(span, stage, "empty pipeline stage"))?;
+                commands.push(command);
+            }
+            None => {
+                let end = lexer.span();
+                return match current.take() {
+                    Some(command) => {
+                        commands.push(command);
+                        Ok(Some(Pipeline { commands }))
+                    }
+                    None if commands.is_empty() => Ok(None),
+                    None => Err(lexer_error(end, stage, "empty pipeline stage")),
+                };
+            }
+            Some((Token::Word(word), span)) => match current.as_mut() {
+                Some(command) => command.args.push(classify(word)),
+                None => match classify(word) {
+                    Arg::Word(name) => current = Some(Command { name, args: Vec::new(), stage, span }),
+                    other => {
+                        let message = format!("expected a command name, found '{}'", other.text());
+                        return Err(lexer_error(span, stage, message));
+                    }
+                },
+            },
+            Some((Token::Quoted(value), span)) => match current.as_mut() {
+                Some(command) => command.args.push(Arg::Str(value)),
+                None => return Err(lexer_error(span, stage, "expected a command name, found a quoted string")),
+            },
+        }
+    }
+}
+
+fn lexer_error(span: Span, stage: usize, message: impl Into<String>) -> ParseError {
+    ParseError { message: message.into(), line: span.line, column: span.column, stage }
+}
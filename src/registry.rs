@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::dataframe::DataFrame;
+
+/// What an argument is expected to hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    Number,     // Any float
+    Integer,    // Signed whole number
+    Count,      // Non-negative whole number
+    Date,       // YYYY-MM-DD
+    Text,       // Free text such as a column name
+    Path,       // File path
+    Frame,      // Name of a bound variable, passed to the operation as an input frame
+}
+
+impl fmt::Display for ArgKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArgKind::Number => "number",
+            ArgKind::Integer => "integer",
+            ArgKind::Count => "count",
+            ArgKind::Date => "date",
+            ArgKind::Text => "text",
+            ArgKind::Path => "path",
+            ArgKind::Frame => "variable",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Declaration of one positional argument of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub default: Option<&'static str>,  // Value used when the argument is left out
+    pub optional: bool,
+    pub variadic: bool,                 // Consumes all remaining arguments (at least one)
+}
+
+impl ArgSpec {
+    pub const fn required(name: &'static str, kind: ArgKind) -> Self {
+        ArgSpec { name, kind, default: None, optional: false, variadic: false }
+    }
+
+    /// An argument that falls back to `default` when left out
+    pub const fn with_default(name: &'static str, kind: ArgKind, default: &'static str) -> Self {
+        ArgSpec { name, kind, default: Some(default), optional: true, variadic: false }
+    }
+
+    /// An argument that may be left out entirely
+    pub const fn optional(name: &'static str, kind: ArgKind) -> Self {
+        ArgSpec { name, kind, default: None, optional: true, variadic: false }
+    }
+
+    pub const fn variadic(name: &'static str, kind: ArgKind) -> Self {
+        ArgSpec { name, kind, default: None, optional: false, variadic: true }
+    }
+
+    /// Check that a value can be parsed as this argument's kind
+    pub fn validate(&self, value: &str) -> std::result::Result<(), String> {
+        let valid = match self.kind {
+            ArgKind::Number => value.parse::<f64>().is_ok(),
+            ArgKind::Integer => value.parse::<i64>().is_ok(),
+            ArgKind::Count => value.parse::<usize>().is_ok(),
+            ArgKind::Date => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+            ArgKind::Text | ArgKind::Path | ArgKind::Frame => !value.is_empty(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("invalid value '{}' for {}, expected a {}", value, self, self.kind))
+        }
+    }
+}
+
+impl fmt::Display for ArgSpec {
+    /// `<name:kind>` for required arguments, `[name:kind=default]` for optional ones
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.optional, self.default) {
+            (true, Some(default)) => write!(f, "[{}:{}={}]", self.name, self.kind, default),
+            (true, None) => write!(f, "[{}:{}]", self.name, self.kind),
+            _ if self.variadic => write!(f, "<{}:{}>...", self.name, self.kind),
+            _ => write!(f, "<{}:{}>", self.name, self.kind),
+        }
+    }
+}
+
+/// Non-frame arguments handed to `Operation::apply`, with defaults already filled in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    values: Vec<String>,
+}
+
+impl Args {
+    pub fn new(values: Vec<String>) -> Self {
+        Args { values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.values.get(index).map(String::as_str)
+    }
+
+    /// The argument at `index`, or an error naming it if it is missing
+    pub fn text(&self, index: usize) -> Result<&str> {
+        self.get(index).ok_or_else(|| Error::InvalidValue(format!("Missing argument {}", index + 1)))
+    }
+
+    pub fn parse<T>(&self, index: usize) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let text = self.text(index)?;
+        text.parse::<T>().map_err(|e| Error::InvalidValue(format!("Invalid argument '{}': {}", text, e)))
+    }
+
+    /// All arguments from `index` onwards, e.g. the values of a variadic argument
+    pub fn rest(&self, index: usize) -> &[String] {
+        self.values.get(index..).unwrap_or(&[])
+    }
+}
+
+/// A pipeline verb. Implement this and register it on an `Engine` to add new commands
+/// without touching the engine itself.
+pub trait Operation: Send + Sync {
+    /// Command name used in pipelines
+    fn name(&self) -> &str;
+
+    /// One-line description
+    fn help(&self) -> &str;
+
+    /// Positional arguments, in order
+    fn args(&self) -> &[ArgSpec] {
+        &[]
+    }
+
+    /// Number of input frames. Frames come from `Frame` arguments first, with the
+    /// current frame prepended when fewer were named.
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame>;
+}
+
+/// Usage line of an operation, e.g. `momentum <lookback:count> [frequency:count=1]`
+pub fn signature(op: &dyn Operation) -> String {
+    let mut signature = op.name().to_string();
+    for spec in op.args() {
+        signature.push(' ');
+        signature.push_str(&spec.to_string());
+    }
+    signature
+}
+
+/// Detailed help for one operation: usage, description and arguments
+pub fn describe(op: &dyn Operation) -> String {
+    let mut text = format!("Usage: {}\n\n  {}\n", signature(op), op.help());
+    if !op.args().is_empty() {
+        text.push_str("\nArguments:\n");
+        for spec in op.args() {
+            let detail = match (spec.default, spec.optional, spec.variadic) {
+                (Some(default), _, _) => format!("default {}", default),
+                (None, true, _) => "optional".to_string(),
+                (None, false, true) => "one or more".to_string(),
+                _ => "required".to_string(),
+            };
+            text.push_str(&format!("  {:<12} {:<9} {}\n", spec.name, spec.kind.to_string(), detail));
+        }
+        if op.args().iter().any(|spec| !spec.variadic) {
+            text.push_str("\nArguments can also be given by name, as name=value.\n");
+        }
+    }
+    text
+}
+
+/// Operations available to an engine, keyed by name
+#[derive(Clone, Default)]
+pub struct Registry {
+    ops: BTreeMap<String, Arc<dyn Operation>>,
+}
+
+impl Registry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Registry { ops: BTreeMap::new() }
+    }
+
+    /// A registry holding every built-in operation
+    pub fn with_builtins() -> Self {
+        let mut registry = Registry::new();
+        for op in crate::operations::builtins() {
+            registry.ops.insert(op.name().to_string(), op);
+        }
+        registry
+    }
+
+    /// Add an operation, replacing any existing one of the same name
+    pub fn register<O: Operation + 'static>(&mut self, op: O) {
+        self.ops.insert(op.name().to_string(), Arc::new(op));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Operation> {
+        self.ops.get(name).map(|op| op.as_ref())
+    }
+
+    /// Registered operation names in sorted order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.ops.keys().map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Operation> {
+        self.ops.values().map(|op| op.as_ref())
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::error::ReadlineError;
+use rustyline::{Context, Editor, Helper};
+
+use crate::error::Result;
+use crate::dataframe::DataFrame;
+use crate::engine::Engine;
+use crate::parser::parse_pipeline;
+
+/// Tab-completion of command names, column names and variables
+#[derive(Default)]
+pub struct ReplHelper {
+    pub commands: Vec<String>,
+    pub columns: Vec<String>,
+    pub vars: Vec<String>,
+}
+
+impl ReplHelper {
+    /// Refresh the completion candidates from the engine state
+    pub fn refresh(&mut self, engine: &Engine, current_df: Option<&DataFrame>) {
+        self.commands = engine.command_names();
+        self.vars = engine.var_names().into_iter().cloned().collect();
+        let mut columns: Vec<String> = current_df.map(|df| df.column_names.clone()).unwrap_or_default();
+        for name in &self.vars {
+            if let Ok(df) = engine.var(name) {
+                columns.extend(df.column_names.iter().cloned());
+            }
+        }
+        columns.sort();
+        columns.dedup();
+        self.columns = columns;
+    }
+
+    /// Candidates for the word ending at `pos`, with the byte offset where that word starts
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = before.rfind(|c: char| c.is_whitespace() || c == '|').map_or(0, |i| i + 1);
+        let word = &before[start..];
+        let is_command = before[..start].trim_end().is_empty() || before[..start].trim_end().ends_with('|');
+
+        let mut matches: Vec<String> = if is_command {
+            self.commands.iter().filter(|c| c.starts_with(word)).cloned().collect()
+        } else if let Some(var) = word.strip_prefix('$') {
+            self.vars.iter().filter(|v| v.starts_with(var)).map(|v| format!("${}", v)).collect()
+        } else {
+            self.columns.iter().chain(self.vars.iter()).filter(|c| c.starts_with(word)).cloned().collect()
+        };
+        matches.sort();
+        matches.dedup();
+        (start, matches)
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, matches) = self.candidates(line, pos);
+        let pairs = matches.into_iter().map(|m| Pair { display: m.clone(), replacement: m }).collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".light_r_history"))
+}
+
+/// Run an interactive session; the engine, its variables and the current frame live
+/// across lines so data is only parsed once
+pub fn run() -> Result<()> {
+    let mut engine = Engine::new();
+    let mut current_df: Option<DataFrame> = None;
+
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    let mut helper = ReplHelper::default();
+    helper.refresh(&engine, None);
+    editor.set_helper(Some(helper));
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session
+        let _ = editor.load_history(path);
+    }
+
+    println!("light-r REPL. Type a pipeline, `help` for commands, `exit` to quit.");
+
+    loop {
+        let line = match editor.readline("light-r> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err.into()),
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        editor.add_history_entry(trimmed)?;
+        if trimmed == "exit" || trimmed == "quit" {
+            break;
+        }
+        if trimmed == "help" || trimmed.starts_with("help ") {
+            let topic = trimmed.split_whitespace().nth(1);
+            match engine.help(topic) {
+                Ok(text) => print!("{}", text),
+                Err(err) => eprintln!("Error: {}", err),
+            }
+            continue;
+        }
+
+        match eval_line(&mut engine, trimmed, current_df.clone()) {
+            Ok(Some(df)) => {
+                println!("[{} rows x {} columns]", df.num_rows, df.num_columns);
+                current_df = Some(df);
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("Error: {}", err.render(trimmed)),
+        }
+
+        if let Some(helper) = editor.helper_mut() {
+            helper.refresh(&engine, current_df.as_ref());
+        }
+    }
+
+    if let Some(path) = &history {
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
+
+/// Evaluate one line against the current frame; a failed line leaves the frame untouched
+pub fn eval_line(engine: &mut Engine, line: &str, current_df: Option<DataFrame>) -> Result<Option<DataFrame>> {
+    let pipeline = parse_pipeline(line)?;
+    engine.execute_pipeline(&pipeline, current_df)
+}
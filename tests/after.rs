@@ -1,37 +1,36 @@
 use chrono::NaiveDate;
 use light_r::{DataFrame, operations::after};
 
+fn sample_df() -> DataFrame {
+    let dates: Vec<String> = ["2021-01-01", "2021-01-02", "2021-01-03", "2021-01-04", "2021-01-05"]
+        .iter().map(|d| d.to_string()).collect();
+    let data = vec![vec![1.0], vec![2.0], vec![3.0], vec![4.0], vec![5.0]];
+    let column_names = vec!["return".to_string()];
+    DataFrame::from_rows(dates, column_names, data, "row").unwrap()
+}
+
 #[test]
 fn test_after_date() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 4).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 5).unwrap(),
-    ];
-    let data = vec![vec![1.0, 2.0, 3.0, 4.0, 5.0]];
-    let column_names = vec!["date", "return"];
-    let df = DataFrame {dates, data, column_names};
+    let df = sample_df();
 
-    let result = after::after_date(&df, NaiveDate::from_ymd_opt(2021, 1, 3).unwrap());
-    assert_eq!(result.dates.len(), 2);
-    assert_eq!(result.dates[0], NaiveDate::from_ymd_opt(2021, 1, 4).unwrap());
+    let result = after::after_date(&df, NaiveDate::from_ymd_opt(2021, 1, 3).unwrap()).unwrap();
+    assert_eq!(result.num_rows, 2);
+    assert_eq!(result.index.label(0), "2021-01-04");
+    assert_eq!(result.get(0, 0), 4.0);
+    assert_eq!(result.get(1, 0), 5.0);
 }
 
 #[test]
 fn test_main() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 4).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 5).unwrap(),
-    ];
-    let data = vec![vec![1.0, 2.0, 3.0, 4.0, 5.0]];
-    let df = DataFrame { dates, data };
+    let df = sample_df();
 
     let result = after::main(&df, "2021-01-03").unwrap();
-    assert_eq!(result.dates.len(), 2);
-    assert_eq!(result.dates[0], NaiveDate::from_ymd_opt(2021, 1, 4).unwrap());
+    assert_eq!(result.num_rows, 2);
+    assert_eq!(result.index.label(0), "2021-01-04");
+}
+
+#[test]
+fn test_main_invalid_date() {
+    let df = sample_df();
+    assert!(after::main(&df, "03/01/2021").is_err());
 }
@@ -0,0 +1,63 @@
+use light_r::align::{align, Join};
+use light_r::{Error, Index};
+
+fn index(labels: &[&str]) -> Index {
+    Index::from(labels.iter().map(|label| label.to_string()).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_identical_indexes_match_row_for_row() {
+    let dates = index(&["2021-01-01", "2021-01-02"]);
+    for join in [Join::Inner, Join::Left, Join::Outer, Join::Asof] {
+        let alignment = align(&dates, &dates, join).unwrap();
+        assert_eq!(alignment.index, dates);
+        assert_eq!(alignment.left, vec![Some(0), Some(1)]);
+        assert_eq!(alignment.right, alignment.left);
+    }
+}
+
+#[test]
+fn test_asof_takes_the_latest_earlier_row() {
+    let intraday = index(&["2021-01-04 09:00", "2021-01-04 17:00", "2021-01-05 09:00", "2021-01-06 09:00"]);
+    let daily = index(&["2021-01-04", "2021-01-06"]);
+    let alignment = align(&intraday, &daily, Join::Asof).unwrap();
+    assert_eq!(alignment.index, intraday);
+    assert_eq!(alignment.right, vec![Some(0), Some(0), Some(0), Some(1)]);
+
+    // Rows before the first right row have no match
+    let alignment = align(&daily, &index(&["2021-01-05"]), Join::Asof).unwrap();
+    assert_eq!(alignment.right, vec![None, Some(0)]);
+}
+
+#[test]
+fn test_outer_join_merges_in_time_order() {
+    let alignment = align(&index(&["2021-01-02", "2021-01-04"]), &index(&["2021-01-01", "2021-01-04", "2021-01-05"]), Join::Outer).unwrap();
+    assert_eq!(alignment.index.labels(), vec!["2021-01-01", "2021-01-02", "2021-01-04", "2021-01-05"]);
+    assert_eq!(alignment.left, vec![None, Some(0), Some(1), None]);
+    assert_eq!(alignment.right, vec![Some(0), None, Some(1), Some(2)]);
+}
+
+#[test]
+fn test_labels_match_by_equality() {
+    let alignment = align(&index(&["b", "a", "c"]), &index(&["c", "d", "b"]), Join::Outer).unwrap();
+    assert_eq!(alignment.index.labels(), vec!["b", "a", "c", "d"]);
+    assert_eq!(alignment.right, vec![Some(2), None, Some(0), Some(1)]);
+    let inner = align(&index(&["b", "a", "c"]), &index(&["c", "d", "b"]), Join::Inner).unwrap();
+    assert_eq!(inner.index.labels(), vec!["b", "c"]);
+    assert!(matches!(align(&index(&["a"]), &index(&["b"]), Join::Asof), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_incompatible_indexes() {
+    let zoned = index(&["2021-01-04T09:00:00+01:00"]);
+    let utc = index(&["2021-01-04T09:00:00Z"]);
+    assert!(matches!(align(&zoned, &utc, Join::Inner), Err(Error::Index(_))));
+    assert!(matches!(align(&index(&["2021-01-04"]), &index(&["x"]), Join::Left), Err(Error::Index(_))));
+    // Left-based joins keep the left index even when the types differ
+    let daily = index(&["2021-01-04"]);
+    assert_eq!(align(&daily, &index(&["2021-01-04 00:00"]), Join::Left).unwrap().right, vec![Some(0)]);
+    assert!(matches!(align(&daily, &index(&["2021-01-05 00:00"]), Join::Outer), Err(Error::Index(_))));
+
+    assert_eq!("ffill".parse::<Join>().unwrap(), Join::Asof);
+    assert!("cross".parse::<Join>().is_err());
+}
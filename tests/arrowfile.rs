@@ -0,0 +1,190 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, Date32Array, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use light_r::arrowfile::{self, Format};
+use light_r::dataframe::IndexColumn;
+use light_r::operations::{load, save};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error, Index};
+use parquet::arrow::ArrowWriter;
+use tempfile::TempDir;
+
+fn sample(row_or_column: &str) -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-04".to_string(), "2021-01-05".to_string()];
+    let columns = vec!["SP500".to_string(), "US2".to_string()];
+    let data = vec![vec![3700.5, f64::NAN], vec![3641.0, 110.25], vec![3726.9, -0.5]];
+    DataFrame::from_rows(dates, columns, data, row_or_column).unwrap()
+}
+
+fn same_values(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x == y || (x.is_nan() && y.is_nan()))
+}
+
+#[test]
+fn test_format_from_extension() {
+    assert_eq!(Format::from_path("prices.parquet"), Some(Format::Parquet));
+    assert_eq!(Format::from_path("dir.v2/prices.PQ"), Some(Format::Parquet));
+    assert_eq!(Format::from_path("prices.arrow"), Some(Format::Ipc));
+    assert_eq!(Format::from_path("prices.feather"), Some(Format::Ipc));
+    assert_eq!(Format::from_path("prices.csv"), None);
+    assert_eq!(Format::from_path("prices"), None);
+}
+
+#[test]
+fn test_round_trip_both_formats_and_layouts() {
+    let dir = TempDir::new().unwrap();
+    for name in ["prices.parquet", "prices.arrow"] {
+        let path = dir.path().join(name).to_str().unwrap().to_string();
+        for layout in ["row", "column"] {
+            let df = sample(layout);
+            save::save(&df, &path).unwrap();
+            let loaded = load::load(&path).unwrap();
+            assert_eq!(loaded.index, df.index);
+            assert_eq!(loaded.column_names, df.column_names);
+            for col in 0..2 {
+                assert!(same_values(&loaded.column(col), &df.column(col)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_timestamp_index_and_numeric_columns() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("lake.parquet").to_str().unwrap().to_string();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ticker", DataType::Utf8, false),
+        Field::new("close", DataType::Int64, true),
+        Field::new("ts", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+    ]));
+    let day = 86_400_000;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(vec!["ES", "ES"])),
+        Arc::new(Int64Array::from(vec![Some(10), None])),
+        Arc::new(TimestampMillisecondArray::from(vec![18_628 * day, 18_629 * day + 3_600_000])),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    // The timestamp column is the index even though it isn't first; strings are skipped
+    let df = arrowfile::read(&path, Format::Parquet, None, "row").unwrap();
+    assert!(matches!(df.index, Index::Timestamps(_, None)));
+    assert_eq!(df.index.labels(), vec!["2021-01-01 00:00:00", "2021-01-02 01:00:00"]);
+    assert_eq!(df.column_names, vec!["close"]);
+    assert_eq!(df.get(0, 0), 10.0);
+    assert!(df.get(1, 0).is_nan());
+
+    let by_name = arrowfile::read(&path, Format::Parquet, Some(&IndexColumn::Name("ticker".to_string())), "row").unwrap();
+    assert_eq!(by_name.index.labels(), vec!["ES", "ES"]);
+    let missing = arrowfile::read(&path, Format::Parquet, Some(&IndexColumn::Name("date".to_string())), "row");
+    assert!(matches!(missing, Err(Error::MissingColumn(name)) if name == "date"));
+}
+
+#[test]
+fn test_explicit_index_position_overrides_date_detection() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("settles.parquet").to_str().unwrap().to_string();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("ts", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+        Field::new("settle", DataType::Date32, false),
+        Field::new("close", DataType::Int64, true),
+    ]));
+    let day = 86_400_000;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(TimestampMillisecondArray::from(vec![18_628 * day, 18_629 * day])),
+        Arc::new(Date32Array::from(vec![18_630, 18_631])),
+        Arc::new(Int64Array::from(vec![Some(10), Some(11)])),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+    let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let settles = arrowfile::read(&path, Format::Parquet, Some(&IndexColumn::Position(1)), "row").unwrap();
+    assert_eq!(settles.index.labels(), vec!["2021-01-03", "2021-01-04"]);
+    assert_eq!(settles.column_names, vec!["close"]);
+    let beyond = arrowfile::read(&path, Format::Parquet, Some(&IndexColumn::Position(3)), "row");
+    assert!(matches!(beyond, Err(Error::MissingColumn(_))));
+
+    let mut engine = Engine::new();
+    let mut run = |line: &str| engine.execute_pipeline(&parse_pipeline(line).unwrap(), None).unwrap().unwrap();
+    assert_eq!(run(&format!("load {} , 1", path)).index.labels(), vec!["2021-01-03", "2021-01-04"]);
+    assert_eq!(run(&format!("load {} index=0", path)).index.labels(), vec!["2021-01-01 00:00:00", "2021-01-02 00:00:00"]);
+    assert_eq!(run(&format!("load {}", path)).index.labels(), vec!["2021-01-01 00:00:00", "2021-01-02 00:00:00"]);
+}
+
+#[test]
+fn test_named_time_zones_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let write_zoned = |zone: &str| {
+        let path = dir.path().join(format!("{}.arrow", zone.replace('/', "_"))).to_str().unwrap().to_string();
+        let times = TimestampMillisecondArray::from(vec![1_609_459_200_000, 1_609_545_600_000]).with_timezone(zone);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ts", times.data_type().clone(), false),
+            Field::new("close", DataType::Int64, true),
+        ]));
+        let columns: Vec<ArrayRef> = vec![Arc::new(times), Arc::new(Int64Array::from(vec![1, 2]))];
+        let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+        let mut writer = arrow_ipc::writer::FileWriter::try_new(File::create(&path).unwrap(), &schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        arrowfile::read(&path, Format::Ipc, None, "row")
+    };
+
+    let tokyo = write_zoned("+09:00").unwrap();
+    assert_eq!(tokyo.index.labels(), vec!["2021-01-01 09:00:00+09:00", "2021-01-02 09:00:00+09:00"]);
+    assert!(matches!(write_zoned("UTC").unwrap().index, Index::Timestamps(_, Some(offset)) if offset.local_minus_utc() == 0));
+    assert!(matches!(write_zoned("America/New_York"), Err(Error::Index(message)) if message.contains("America/New_York")));
+}
+
+#[test]
+fn test_non_date_row_names_are_written_as_strings() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("labels.arrow").to_str().unwrap().to_string();
+    let df = DataFrame::from_rows(vec!["a".to_string(), "b".to_string()], vec!["X".to_string()], vec![vec![1.0], vec![2.0]], "row").unwrap();
+    save::save(&df, &path).unwrap();
+    let loaded = load::load(&path).unwrap();
+    assert_eq!(loaded.index.labels(), vec!["a", "b"]);
+    assert_eq!(loaded.column(0).to_vec(), vec![1.0, 2.0]);
+}
+
+#[test]
+fn test_corrupt_file_is_a_format_error() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("bad.parquet").to_str().unwrap().to_string();
+    std::fs::write(&path, "DATE,A\n2021-01-01,1\n").unwrap();
+    assert!(matches!(load::load(&path), Err(Error::Format(_))));
+}
+
+#[test]
+fn test_pipeline_converts_between_formats() {
+    let dir = TempDir::new().unwrap();
+    let parquet = dir.path().join("in.parquet").to_str().unwrap().to_string();
+    let arrow = dir.path().join("out.arrow").to_str().unwrap().to_string();
+    save::save(&sample("column"), &parquet).unwrap();
+
+    let mut engine = Engine::new();
+    let pipeline = parse_pipeline(&format!("load {} | cgrep SP500 | save {}", parquet, arrow)).unwrap();
+    engine.execute_pipeline(&pipeline, None).unwrap();
+    let loaded = load::load(&arrow).unwrap();
+    assert_eq!(loaded.column_names, vec!["SP500"]);
+    assert_eq!(loaded.column(0).to_vec(), vec![3700.5, 3641.0, 3726.9]);
+}
+
+#[test]
+fn test_zoned_timestamps_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let index: Vec<String> = vec!["2021-01-01T09:30:00+01:00".to_string(), "2021-01-01T10:45:00.5+01:00".to_string()];
+    let df = DataFrame::from_rows(index, vec!["A".to_string()], vec![vec![1.0], vec![2.0]], "column").unwrap();
+    for name in ["zoned.parquet", "zoned.arrow"] {
+        let path = dir.path().join(name).to_str().unwrap().to_string();
+        save::save(&df, &path).unwrap();
+        assert_eq!(load::load(&path).unwrap().index, df.index);
+    }
+}
@@ -1,36 +1,29 @@
 use chrono::NaiveDate;
 use light_r::{DataFrame, operations::before};
 
+fn sample_df() -> DataFrame {
+    let dates: Vec<String> = ["2021-01-01", "2021-01-02", "2021-01-03", "2021-01-04", "2021-01-05"]
+        .iter().map(|d| d.to_string()).collect();
+    let data = vec![vec![1.0], vec![2.0], vec![3.0], vec![4.0], vec![5.0]];
+    let column_names = vec!["return".to_string()];
+    DataFrame::from_rows(dates, column_names, data, "column").unwrap()
+}
+
 #[test]
 fn test_before_date() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 4).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 5).unwrap(),
-    ];
-    let data = vec![vec![1.0, 2.0, 3.0, 4.0, 5.0]];
-    let df = DataFrame { dates, data };
+    let df = sample_df();
 
-    let result = before::before_date(&df, NaiveDate::from_ymd_opt(2021, 1, 4).unwrap());
-    assert_eq!(result.dates.len(), 3);
-    assert_eq!(result.dates.last().unwrap(), &NaiveDate::from_ymd_opt(2021, 1, 3).unwrap());
+    let result = before::before_date(&df, NaiveDate::from_ymd_opt(2021, 1, 4).unwrap()).unwrap();
+    assert_eq!(result.num_rows, 3);
+    assert_eq!(result.index.label(result.num_rows - 1), "2021-01-03");
+    assert_eq!(result.get(2, 0), 3.0);
 }
 
 #[test]
 fn test_main() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 4).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 5).unwrap(),
-    ];
-    let data = vec![vec![1.0, 2.0, 3.0, 4.0, 5.0]];
-    let df = DataFrame { dates, data };
+    let df = sample_df();
 
     let result = before::main(&df, "2021-01-04").unwrap();
-    assert_eq!(result.dates.len(), 3);
-    assert_eq!(result.dates.last().unwrap(), &NaiveDate::from_ymd_opt(2021, 1, 3).unwrap());
+    assert_eq!(result.num_rows, 3);
+    assert_eq!(result.index.label(result.num_rows - 1), "2021-01-03");
 }
@@ -0,0 +1,145 @@
+use light_r::align::Join;
+use light_r::{DataFrame, Engine, Error, operations::{binary, cgrep}};
+use approx::assert_relative_eq;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+fn pair() -> (DataFrame, DataFrame) {
+    let column_names = vec!["A".to_string(), "B".to_string()];
+    let df1 = DataFrame::from_rows(dates(2), column_names.clone(), vec![vec![6.0, 8.0], vec![1.0, 0.0]], "row").unwrap();
+    let df2 = DataFrame::from_rows(dates(2), column_names, vec![vec![2.0, 4.0], vec![4.0, 0.0]], "column").unwrap();
+    (df1, df2)
+}
+
+#[test]
+fn test_add() {
+    let (df1, df2) = pair();
+    let result = binary::add(&df1, &df2).unwrap();
+    assert_eq!(result.row(0), vec![8.0, 12.0]);
+    assert_eq!(result.row(1), vec![5.0, 0.0]);
+}
+
+#[test]
+fn test_subtract() {
+    let (df1, df2) = pair();
+    let result = binary::subtract(&df1, &df2).unwrap();
+    assert_eq!(result.row(0), vec![4.0, 4.0]);
+    assert_eq!(result.row(1), vec![-3.0, 0.0]);
+}
+
+#[test]
+fn test_divide() {
+    let (df1, df2) = pair();
+    let result = binary::divide(&df1, &df2).unwrap();
+    assert_relative_eq!(result.get(0, 0), 3.0, epsilon = 1e-6);
+    assert_relative_eq!(result.get(1, 0), 0.25, epsilon = 1e-6);
+    assert!(result.get(1, 1).is_nan()); // 0 / 0
+}
+
+#[test]
+fn test_apply_mismatched_dates_joins_inner() {
+    let (df1, _) = pair();
+    let df2 = DataFrame::from_rows(dates(1), vec!["A".to_string(), "B".to_string()], vec![vec![1.0, 1.0]], "row").unwrap();
+    let result = binary::add(&df1, &df2).unwrap();
+    assert_eq!(result.index.labels(), vec!["2021-01-01"]);
+    assert_eq!(result.row(0), vec![7.0, 9.0]);
+}
+
+/// Futures on two holiday calendars: `us` misses the 4th, `eu` misses the 5th
+fn calendars() -> (DataFrame, DataFrame) {
+    let us = DataFrame::from_rows(
+        vec!["2021-01-01".to_string(), "2021-01-05".to_string(), "2021-01-06".to_string()],
+        vec!["A".to_string(), "B".to_string()],
+        vec![vec![1.0, 10.0], vec![2.0, 20.0], vec![3.0, 30.0]],
+        "row",
+    )
+    .unwrap();
+    let eu = DataFrame::from_rows(
+        vec!["2021-01-01".to_string(), "2021-01-04".to_string(), "2021-01-06".to_string()],
+        vec!["B".to_string(), "C".to_string()],
+        vec![vec![100.0, 1000.0], vec![200.0, 2000.0], vec![300.0, 3000.0]],
+        "column",
+    )
+    .unwrap();
+    (us, eu)
+}
+
+fn same_values(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x == y || (x.is_nan() && y.is_nan()))
+}
+
+#[test]
+fn test_join_modes() {
+    let (us, eu) = calendars();
+    let nan = f64::NAN;
+
+    let inner = binary::apply_with(&us, &eu, Join::Inner, |a, b| a + b).unwrap();
+    assert_eq!(inner.index.labels(), vec!["2021-01-01", "2021-01-06"]);
+    assert_eq!(inner.column_names, vec!["B"]);
+    assert_eq!(inner.column(0).to_vec(), vec![110.0, 330.0]);
+    assert_eq!(inner.row_or_column, "row");
+
+    let left = binary::apply_with(&us, &eu, Join::Left, |a, b| a + b).unwrap();
+    assert_eq!(left.index, us.index);
+    assert_eq!(left.column_names, vec!["A", "B"]);
+    assert!(left.column(0).iter().all(|x| x.is_nan()));
+    assert!(same_values(&left.column(1), &[110.0, nan, 330.0]));
+
+    let asof = binary::apply_with(&us, &eu, Join::Asof, |a, b| a + b).unwrap();
+    assert_eq!(asof.index, us.index);
+    assert!(same_values(&asof.column(1), &[110.0, 220.0, 330.0]));
+
+    let outer = binary::apply_with(&us, &eu, Join::Outer, |a, b| a + b).unwrap();
+    assert_eq!(outer.index.labels(), vec!["2021-01-01", "2021-01-04", "2021-01-05", "2021-01-06"]);
+    assert_eq!(outer.column_names, vec!["A", "B", "C"]);
+    assert!(same_values(&outer.column(1), &[110.0, nan, nan, 330.0]));
+}
+
+#[test]
+fn test_single_column_frames_broadcast() {
+    let (us, eu) = calendars();
+    let b = cgrep::main(&eu, &["B".to_string()]).unwrap();
+    let scaled = binary::apply_with(&us, &b, Join::Asof, |a, b| a * b).unwrap();
+    assert_eq!(scaled.column_names, vec!["A", "B"]);
+    assert_eq!(scaled.row(1), vec![400.0, 4000.0]);
+
+    // On the left, the single column takes the other frame's names
+    let flipped = binary::apply_with(&b, &us, Join::Inner, |a, b| a - b).unwrap();
+    assert_eq!(flipped.column_names, vec!["A", "B"]);
+    assert_eq!(flipped.row(1), vec![297.0, 270.0]);
+}
+
+#[test]
+fn test_alignment_errors() {
+    let (us, eu) = calendars();
+    let other = DataFrame::from_rows(dates(3), vec!["X".to_string(), "Y".to_string()], vec![vec![0.0; 2]; 3], "row").unwrap();
+    assert!(matches!(binary::add(&us, &other), Err(Error::Shape(_))));
+
+    let labels = DataFrame::from_rows(vec!["a".to_string()], vec!["B".to_string()], vec![vec![1.0]], "row").unwrap();
+    assert!(matches!(binary::add(&eu, &labels), Err(Error::Index(_))));
+    // Identical indexes match row for row, whatever their type
+    assert!(binary::apply_with(&labels, &labels.clone(), Join::Asof, |a, b| a + b).is_ok());
+}
+
+#[test]
+fn test_join_argument_in_a_pipeline() {
+    let (us, eu) = calendars();
+    let mut engine = Engine::new();
+    engine.set_var("eu", eu);
+    let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let asof = engine.execute_command("add", &args(&["eu", "join=asof"]), Some(us.clone())).unwrap();
+    assert_eq!(asof.index, us.index);
+    assert_eq!(asof.get(1, 1), 220.0);
+    let err = engine.execute_command("add", &args(&["eu", "join=sideways"]), Some(us.clone())).err().unwrap();
+    assert!(matches!(err, Error::InvalidValue(_)));
+
+    // The join mode can also be given positionally, in place of the second frame
+    let positional = engine.execute_command("add", &args(&["eu", "asof"]), Some(us.clone())).unwrap();
+    assert_eq!(positional.index, asof.index);
+    assert_eq!(positional.get(1, 1), 220.0);
+    engine.set_var("us", us.clone());
+    let both = engine.execute_command("add", &args(&["us", "eu", "outer"]), None).unwrap();
+    assert_eq!(both.num_rows, 4);
+}
@@ -0,0 +1,127 @@
+use light_r::metadata;
+use light_r::operations::{load_bin, save_bin};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error, Index, Tags};
+use std::collections::BTreeMap;
+use std::fs;
+use tempfile::{NamedTempFile, TempDir};
+
+fn sample(row_or_column: &str) -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string(), "2021-01-03".to_string()];
+    let columns = vec!["A".to_string(), "Börse".to_string()];
+    let data = vec![vec![1.0, f64::NAN], vec![3.5, -4.0], vec![5.0, 6.25]];
+    DataFrame::from_rows(dates, columns, data, row_or_column).unwrap()
+}
+
+fn temp_path() -> (NamedTempFile, String) {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    (file, path)
+}
+
+#[test]
+fn test_round_trip_in_both_layouts() {
+    for layout in ["row", "column"] {
+        let df = sample(layout);
+        let (_file, path) = temp_path();
+        save_bin::save_bin(&df, &path).unwrap();
+
+        let loaded = load_bin::load_bin(&path).unwrap();
+        assert_eq!(loaded.row_or_column, layout);
+        assert_eq!(loaded.column_names, df.column_names);
+        assert_eq!(loaded.index, df.index);
+        assert!(loaded.get(0, 1).is_nan());
+        for row in 1..3 {
+            assert_eq!(loaded.row(row), df.row(row));
+        }
+        // Values are read from the mapped file, after the header
+        assert_eq!(loaded.data_offset % 8, 0);
+        assert!(loaded.data_offset > 0);
+    }
+}
+
+#[test]
+fn test_empty_frame_round_trip() {
+    let df = DataFrame::from_rows(Vec::<String>::new(), vec!["A".to_string()], vec![], "column").unwrap();
+    let (_file, path) = temp_path();
+    save_bin::save_bin(&df, &path).unwrap();
+    let loaded = load_bin::load_bin(&path).unwrap();
+    assert_eq!(loaded.num_rows, 0);
+    assert_eq!(loaded.column_names, vec!["A"]);
+}
+
+#[test]
+fn test_tags_round_trip_and_sidecars_apply() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("prices.bin").to_str().unwrap().to_string();
+    let columns = BTreeMap::from([(
+        "Börse".to_string(),
+        Tags::from([("asset_class".to_string(), "equity".to_string()), ("currency".to_string(), "EUR".to_string())]),
+    )]);
+    for layout in ["row", "column"] {
+        let df = metadata::attach(&sample(layout), &columns);
+        save_bin::save_bin(&df, &path).unwrap();
+        let loaded = load_bin::load_bin(&path).unwrap();
+        assert_eq!(loaded.tags, df.tags);
+        assert_eq!(loaded.row(2), df.row(2));
+        assert_eq!(loaded.data_offset % 8, 0);
+    }
+
+    fs::write(dir.path().join("prices.meta.csv"), "column,currency\nA,USD\nBörse,CHF\n").unwrap();
+    let loaded = load_bin::load_bin(&path).unwrap();
+    assert_eq!(loaded.tags[0]["currency"], "USD");
+    assert_eq!(loaded.tags[1]["currency"], "CHF");
+    assert_eq!(loaded.tags[1]["asset_class"], "equity");
+}
+
+#[test]
+fn test_index_type_is_stored_and_validated() {
+    let (_file, path) = temp_path();
+    let rows = || vec![vec![1.0], vec![2.0]];
+    let labels = |labels: &[&str]| labels.iter().map(|label| label.to_string()).collect::<Vec<_>>();
+
+    let times = Index::from(labels(&["2021-01-01 09:30:00+02:00", "2021-01-01 16:00:00.25+02:00"]));
+    assert!(matches!(times, Index::Timestamps(_, Some(_))));
+    // Labels that look like dates stay labels, where inferring from text would make them dates
+    let indexes = [Index::from(labels(&["2021-01-01", "2021-01-04"])), times, Index::Labels(labels(&["2021-01-02", "b"]))];
+    for index in indexes {
+        let df = DataFrame::from_rows(index, vec!["A".to_string()], rows(), "column").unwrap();
+        save_bin::save_bin(&df, &path).unwrap();
+        assert_eq!(load_bin::load_bin(&path).unwrap().index, df.index);
+    }
+
+    for unordered in [["2021-01-04", "2021-01-01"], ["2021-01-04", "2021-01-04"]] {
+        let df = DataFrame::from_rows(labels(&unordered), vec!["A".to_string()], rows(), "row").unwrap();
+        save_bin::save_bin(&df, &path).unwrap();
+        assert!(matches!(load_bin::load_bin(&path), Err(Error::Index(_))));
+    }
+}
+
+#[test]
+fn test_rejects_other_files() {
+    let (_file, path) = temp_path();
+    fs::write(&path, "DATE,A\n2021-01-01,1\n").unwrap();
+    assert!(matches!(load_bin::load_bin(&path), Err(Error::Format(_))));
+
+    save_bin::save_bin(&sample("column"), &path).unwrap();
+    let mut bytes = fs::read(&path).unwrap();
+    fs::write(&path, &bytes[..bytes.len() - 8]).unwrap();
+    assert!(matches!(load_bin::load_bin(&path), Err(Error::Format(_))));
+
+    // A version from the future
+    bytes[8] = 9;
+    fs::write(&path, &bytes).unwrap();
+    assert!(matches!(load_bin::load_bin(&path), Err(Error::Format(_))));
+}
+
+#[test]
+fn test_pipeline_starts_from_load_bin() {
+    let (_file, path) = temp_path();
+    save_bin::save_bin(&sample("column"), &path).unwrap();
+
+    let mut engine = Engine::new();
+    let pipeline = parse_pipeline(&format!("load_bin {} | cgrep A | cumsum 0", path)).unwrap();
+    let result = engine.execute_pipeline(&pipeline, None).unwrap().unwrap();
+    assert_eq!(result.column_names, vec!["A"]);
+    assert_eq!(result.column(0).to_vec(), vec![1.0, 4.5, 9.5]);
+}
@@ -1,49 +1,135 @@
-use chrono::NaiveDate;
-use light_r::{DataFrame, operations::cgrep};
+use std::borrow::Cow;
+use std::sync::Arc;
 
-#[test]
-fn test_filter_columns() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-    ];
+use light_r::{DataFrame, Error, operations::cgrep};
+
+fn sample_df(row_or_column: &str) -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
     let data = vec![
         vec![1.0, 2.0, 3.0],
         vec![4.0, 5.0, 6.0],
     ];
     let column_names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    DataFrame::from_rows(dates, column_names, data, row_or_column).unwrap()
+}
 
-    let result = cgrep::filter_columns(&df, &["A".to_string(), "C".to_string()]);
+#[test]
+fn test_filter_columns() {
+    let df = sample_df("row");
+
+    let result = cgrep::filter_columns(&df, &["A".to_string(), "C".to_string()]).unwrap();
     assert_eq!(result.column_names, vec!["A".to_string(), "C".to_string()]);
-    assert_eq!(result.data, vec![vec![1.0, 3.0], vec![4.0, 6.0]]);
+    assert_eq!(result.row(0), vec![1.0, 3.0]);
+    assert_eq!(result.row(1), vec![4.0, 6.0]);
 }
 
 #[test]
 fn test_main() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-    ];
-    let data = vec![
-        vec![1.0, 2.0, 3.0],
-        vec![4.0, 5.0, 6.0],
-    ];
-    let column_names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    let df = sample_df("column");
 
     let result = cgrep::main(&df, &["A".to_string(), "C".to_string()]).unwrap();
     assert_eq!(result.column_names, vec!["A".to_string(), "C".to_string()]);
-    assert_eq!(result.data, vec![vec![1.0, 3.0], vec![4.0, 6.0]]);
+    assert_eq!(result.row(0), vec![1.0, 3.0]);
+    assert_eq!(result.row(1), vec![4.0, 6.0]);
 }
 
 #[test]
 fn test_main_empty_data() {
-    let df = DataFrame {
-        dates: vec![],
-        data: vec![],
-        column_names: vec![],
-    };
+    let df = DataFrame::from_rows(Vec::<String>::new(), vec![], vec![], "row").unwrap();
     let result = cgrep::main(&df, &["A".to_string()]);
     assert!(result.is_err());
 }
+
+fn futures(row_or_column: &str) -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string(), "2021-01-03".to_string()];
+    let column_names: Vec<String> = ["SP500", "US2", "US10", "BUND", "USDJPY"].iter().map(|s| s.to_string()).collect();
+    let data = (0..3).map(|row| (0..5).map(|col| (row * 10 + col) as f64).collect()).collect();
+    DataFrame::from_rows(dates, column_names, data, row_or_column).unwrap()
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_regex_and_glob_patterns() {
+    let df = futures("column");
+    assert_eq!(cgrep::main(&df, &args(&["US.*"])).unwrap().column_names, vec!["US2", "US10", "USDJPY"]);
+    assert_eq!(cgrep::main(&df, &args(&["US?"])).unwrap().column_names, vec!["US2"]);
+    assert_eq!(cgrep::main(&df, &args(&["US[0-9]*"])).unwrap().column_names, vec!["US2", "US10"]);
+    assert_eq!(cgrep::main(&df, &args(&["*D*"])).unwrap().column_names, vec!["BUND", "USDJPY"]);
+    assert_eq!(cgrep::main(&df, &args(&["re:US\\d+"])).unwrap().column_names, vec!["US2", "US10"]);
+    assert_eq!(cgrep::main(&df, &args(&["glob:[!U]*"])).unwrap().column_names, vec!["SP500", "BUND"]);
+
+    // Patterns match whole names
+    assert!(cgrep::main(&df, &args(&["US"])).unwrap().column_names.is_empty());
+    assert!(matches!(cgrep::main(&df, &args(&["re:US("])), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_names_with_metacharacters_match_exactly() {
+    let dates = vec!["2021-01-01".to_string()];
+    let column_names: Vec<String> = ["CL(1)", "A+B", "ES*", "ESU1"].iter().map(|s| s.to_string()).collect();
+    let df = DataFrame::from_rows(dates, column_names, vec![vec![1.0, 2.0, 3.0, 4.0]], "column").unwrap();
+
+    assert_eq!(cgrep::main(&df, &args(&["-s", "CL(1)"])).unwrap().column_names, vec!["CL(1)"]);
+    assert_eq!(cgrep::main(&df, &args(&["-s", "A+B"])).unwrap().column_names, vec!["A+B"]);
+    assert_eq!(cgrep::main(&df, &args(&["ES*"])).unwrap().column_names, vec!["ES*"]);
+    assert_eq!(cgrep::main(&df, &args(&["-v", "CL(1)", "A+B"])).unwrap().column_names, vec!["ES*", "ESU1"]);
+
+    // Without a column of that name, or with a prefix, the pattern is a regex or glob
+    assert_eq!(cgrep::main(&df, &args(&["glob:ES*"])).unwrap().column_names, vec!["ES*", "ESU1"]);
+    assert_eq!(cgrep::main(&df, &args(&["re:A+B"])).unwrap().column_names, Vec::<String>::new());
+    assert_eq!(cgrep::main(&df, &args(&["CL.*"])).unwrap().column_names, vec!["CL(1)"]);
+    assert!(matches!(cgrep::main(&df, &args(&["CL(2"])), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_pattern_order_sets_column_order() {
+    let df = futures("row");
+    let result = cgrep::main(&df, &args(&["USDJPY", "US*", "SP500"])).unwrap();
+    assert_eq!(result.column_names, vec!["USDJPY", "US2", "US10", "SP500"]);
+    assert_eq!(result.row(1), vec![14.0, 11.0, 12.0, 10.0]);
+    assert_eq!(result.column(3).to_vec(), vec![0.0, 10.0, 20.0]);
+}
+
+#[test]
+fn test_invert_keeps_unmatched_columns_in_frame_order() {
+    let df = futures("column");
+    let result = cgrep::main(&df, &args(&["-v", "SP500", "US*"])).unwrap();
+    assert_eq!(result.column_names, vec!["BUND"]);
+    assert_eq!(result.column(0).to_vec(), vec![3.0, 13.0, 23.0]);
+}
+
+#[test]
+fn test_strict_mode_rejects_unmatched_patterns() {
+    let df = futures("column");
+    assert_eq!(cgrep::main(&df, &args(&["SP500", "VIX"])).unwrap().column_names, vec!["SP500"]);
+    let err = cgrep::main(&df, &args(&["--strict", "SP500", "VIX"])).err().unwrap();
+    assert!(matches!(err, Error::MissingColumn(name) if name == "VIX"));
+    assert!(matches!(cgrep::main(&df, &args(&["-s", "-v", "JP*"])), Err(Error::MissingColumn(_))));
+
+    assert!(matches!(cgrep::main(&df, &args(&["-x", "SP500"])), Err(Error::InvalidValue(_))));
+    assert!(matches!(cgrep::main(&df, &args(&["-v"])), Err(Error::InvalidValue(_))));
+    assert_eq!(cgrep::main(&df, &args(&["--", "-v"])).unwrap().num_columns, 0);
+}
+
+#[test]
+fn test_selection_is_a_view_of_the_parent_mapping() {
+    for layout in ["row", "column"] {
+        let df = futures(layout);
+        let view = cgrep::main(&df, &args(&["BUND", "US2"])).unwrap();
+        assert!(Arc::ptr_eq(&view.mmap, &df.mmap));
+        assert_eq!(view.get(2, 0), 23.0);
+        if layout == "column" {
+            assert!(matches!(view.column(1), Cow::Borrowed(&[1.0, 11.0, 21.0])));
+            assert_eq!(view.values().to_vec(), vec![3.0, 13.0, 23.0, 1.0, 11.0, 21.0]);
+        } else {
+            assert_eq!(view.values().to_vec(), vec![3.0, 1.0, 13.0, 11.0, 23.0, 21.0]);
+        }
+
+        // Views of views map back to the stored columns
+        let inner = cgrep::main(&view, &args(&["US2"])).unwrap().slice_rows(1..3);
+        assert_eq!(inner.column(0).to_vec(), vec![11.0, 21.0]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use light_r::metadata;
+use light_r::operations::cross_section::{self, Transform};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error, Tags};
+use approx::assert_relative_eq;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+const NAN: f64 = f64::NAN;
+
+fn sample(row_or_column: &str) -> DataFrame {
+    let rows = vec![vec![3.0, 1.0, 4.0, 1.0, 5.0], vec![2.0, NAN, -6.0, 0.0, 4.0], vec![NAN; 5]];
+    let names = ["A", "B", "C", "D", "E"].iter().map(|name| name.to_string()).collect();
+    DataFrame::from_rows(dates(3), names, rows, row_or_column).unwrap()
+}
+
+fn assert_row(df: &DataFrame, row: usize, expected: &[f64]) {
+    let actual = df.row(row);
+    for (col, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+        assert!((a.is_nan() && e.is_nan()) || (a - e).abs() < 1e-9, "row {} column {}: {:?} vs {:?}", row, col, actual, expected);
+    }
+}
+
+#[test]
+fn test_rank_averages_ties_and_skips_nan() {
+    for layout in ["row", "column"] {
+        let df = sample(layout);
+        let ranks = cross_section::main(&df, Transform::Rank { pct: false }, None).unwrap();
+        assert_row(&ranks, 0, &[3.0, 1.5, 4.0, 1.5, 5.0]);
+        assert_row(&ranks, 1, &[3.0, NAN, 1.0, 2.0, 4.0]);
+        assert_row(&ranks, 2, &[NAN; 5]);
+        assert_eq!(ranks.row_or_column, layout);
+
+        let pct = cross_section::main(&df, Transform::Rank { pct: true }, None).unwrap();
+        assert_row(&pct, 1, &[0.75, NAN, 0.25, 0.5, 1.0]);
+    }
+}
+
+#[test]
+fn test_zscore_demean_and_normalize() {
+    let df = sample("column");
+    let demeaned = cross_section::main(&df, Transform::Demean, None).unwrap();
+    assert_row(&demeaned, 0, &[0.2, -1.8, 1.2, -1.8, 2.2]);
+    assert_row(&demeaned, 1, &[2.0, NAN, -6.0, 0.0, 4.0]);
+
+    let z = cross_section::main(&df, Transform::Zscore, None).unwrap();
+    // Row 1 has mean 0 and sample variance (4 + 36 + 0 + 16) / 3
+    let std = (56.0f64 / 3.0).sqrt();
+    assert_row(&z, 1, &[2.0 / std, NAN, -6.0 / std, 0.0, 4.0 / std]);
+    assert_relative_eq!(z.row(0).iter().sum::<f64>(), 0.0, epsilon = 1e-12);
+
+    let normalized = cross_section::main(&df, Transform::Normalize, None).unwrap();
+    assert_row(&normalized, 1, &[1.0 / 6.0, NAN, -0.5, 0.0, 1.0 / 3.0]);
+    let zeros = DataFrame::from_rows(dates(1), vec!["A".to_string(), "B".to_string()], vec![vec![0.0, 0.0]], "row").unwrap();
+    assert_row(&cross_section::main(&zeros, Transform::Normalize, None).unwrap(), 0, &[NAN, NAN]);
+}
+
+#[test]
+fn test_winsor_clips_to_quantiles() {
+    let df = sample("row");
+    let clipped = cross_section::main(&df, Transform::Winsor(0.25), None).unwrap();
+    // Row 0 sorted is 1 1 3 4 5: the quartiles are 1 and 4
+    assert_row(&clipped, 0, &[3.0, 1.0, 4.0, 1.0, 4.0]);
+    // Row 1 sorted is -6 0 2 4: the quartiles are -1.5 and 2.5
+    assert_row(&clipped, 1, &[2.0, NAN, -1.5, 0.0, 2.5]);
+    let untouched = cross_section::main(&df, Transform::Winsor(0.0), None).unwrap();
+    assert_row(&untouched, 0, &df.row(0));
+}
+
+#[test]
+fn test_topn() {
+    let df = sample("column");
+    let top = cross_section::main(&df, Transform::TopN(2), None).unwrap();
+    assert_row(&top, 0, &[0.0, 0.0, 1.0, 0.0, 1.0]);
+    assert_row(&top, 1, &[1.0, NAN, 0.0, 0.0, 1.0]);
+    // Ties at the cut go to the leftmost column
+    let bottom = cross_section::main(&df, Transform::TopN(-1), None).unwrap();
+    assert_row(&bottom, 0, &[0.0, 1.0, 0.0, 0.0, 0.0]);
+    let all = cross_section::main(&df, Transform::TopN(10), None).unwrap();
+    assert_row(&all, 1, &[1.0, NAN, 1.0, 1.0, 1.0]);
+}
+
+fn with_sectors(df: &DataFrame) -> DataFrame {
+    let sector = |value: &str| Tags::from([("sector".to_string(), value.to_string())]);
+    let columns = BTreeMap::from([
+        ("A".to_string(), sector("tech")),
+        ("B".to_string(), sector("energy")),
+        ("C".to_string(), sector("tech")),
+        ("D".to_string(), sector("energy")),
+    ]);
+    metadata::attach(df, &columns)
+}
+
+#[test]
+fn test_grouping_by_tag() {
+    let df = with_sectors(&sample("row"));
+    assert_eq!(cross_section::groups(&df, Some("sector")), vec![vec![4], vec![1, 3], vec![0, 2]]);
+
+    let demeaned = cross_section::main(&df, Transform::Demean, Some("sector")).unwrap();
+    // tech is A and C, energy is B and D, and untagged E stands alone
+    assert_row(&demeaned, 0, &[-0.5, 0.0, 0.5, 0.0, 0.0]);
+    assert_row(&demeaned, 1, &[4.0, NAN, -4.0, 0.0, 0.0]);
+    assert_eq!(demeaned.tags, df.tags);
+
+    let ranked = cross_section::main(&df, Transform::Rank { pct: false }, Some("asset_class")).unwrap();
+    assert_row(&ranked, 0, &[3.0, 1.5, 4.0, 1.5, 5.0]);
+}
+
+#[test]
+fn test_cross_section_verbs_in_a_pipeline() {
+    let mut engine = Engine::new();
+    engine.set_var("x", with_sectors(&sample("column")));
+    let mut run = |line: &str| engine.execute_pipeline(&parse_pipeline(line).unwrap(), None);
+
+    assert_row(&run("get x | xrank by=sector").unwrap().unwrap(), 0, &[1.0, 1.5, 2.0, 1.5, 1.0]);
+    // `by` comes first, as in the other cross-sectional verbs
+    assert_row(&run("get x | xrank sector").unwrap().unwrap(), 0, &[1.0, 1.5, 2.0, 1.5, 1.0]);
+    assert_row(&run("get x | xrank sector true").unwrap().unwrap(), 0, &[0.5, 0.75, 1.0, 0.75, 1.0]);
+    assert_row(&run("get x | xrank pct=true").unwrap().unwrap(), 0, &[0.6, 0.3, 0.8, 0.3, 1.0]);
+    assert_row(&run("get x | xzscore sector").unwrap().unwrap(), 2, &[NAN; 5]);
+    assert_row(&run("get x | xtopn -1 sector").unwrap().unwrap(), 1, &[0.0, NAN, 1.0, 1.0, 1.0]);
+    assert_row(&run("get x | xwinsor 0.5").unwrap().unwrap(), 0, &[3.0; 5]);
+    assert_row(&run("get x | xnormalize | xdemean | xzscore").unwrap().unwrap(), 2, &[NAN; 5]);
+
+    assert!(matches!(run("get x | xwinsor 0.6").err().unwrap().root(), Error::InvalidValue(_)));
+    assert!(matches!(run("get x | xtopn 0").err().unwrap().root(), Error::InvalidValue(_)));
+    assert!(matches!(run("get x | xrank sector maybe").err().unwrap().root(), Error::InvalidValue(_)));
+}
@@ -0,0 +1,76 @@
+use light_r::csvfile;
+use light_r::dataframe::CsvOptions;
+use light_r::{DataFrame, Error};
+use std::fs;
+use tempfile::NamedTempFile;
+
+fn temp_path() -> (NamedTempFile, String) {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    (file, path)
+}
+
+/// A few megabytes of CSV, so the parser splits it into several chunks
+fn large_csv() -> String {
+    let mut csv = String::from("DATE, A ,B,C\r\n");
+    for i in 0..120_000 {
+        let special = match i % 97 {
+            0 => "",
+            1 => "n/a",
+            2 => "inf",
+            3 => "-1e-3",
+            _ => " 2.5 ",
+        };
+        csv.push_str(&format!("d{},{}.{},{},{}\r\n", i, i, i % 10, special, -(i as f64) / 7.0));
+        if i % 5000 == 0 {
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+fn same_values(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x == y || (x.is_nan() && y.is_nan()))
+}
+
+#[test]
+fn test_parallel_parse_matches_sequential_reader() {
+    let csv = large_csv();
+    let (_file, path) = temp_path();
+    fs::write(&path, &csv).unwrap();
+
+    for layout in ["row", "column"] {
+        let expected = DataFrame::from_csv_reader(csv.as_bytes(), &CsvOptions::default(), layout).unwrap();
+        for threads in [1, 4] {
+            let df = csvfile::read_with_threads(&path, &CsvOptions::default(), layout, threads).unwrap();
+            assert_eq!(df.column_names, vec!["A", "B", "C"]);
+            assert_eq!(df.index, expected.index);
+            assert!(same_values(&df.values(), &expected.values()));
+        }
+    }
+}
+
+#[test]
+fn test_bad_record_reports_its_line() {
+    let mut csv = large_csv();
+    csv.push_str("late,1,2\n");
+    let (_file, path) = temp_path();
+    fs::write(&path, &csv).unwrap();
+
+    // Physical lines, counting the blank ones
+    let err = csvfile::read_with_threads(&path, &CsvOptions::default(), "row", 4).err().unwrap();
+    assert!(matches!(err, Error::Csv { line, .. } if line == csv.lines().count()));
+}
+
+#[test]
+fn test_quoted_and_empty_files_fall_back() {
+    let (_file, path) = temp_path();
+    fs::write(&path, "\u{feff}DATE;\"A;B\"\n2021-01-01;1,5\n2021-01-02;\"2\"\n").unwrap();
+    let df = csvfile::read(&path, &CsvOptions::default().with_delimiter(b';'), "column").unwrap();
+    assert_eq!(df.column_names, vec!["A;B"]);
+    assert!(df.get(0, 0).is_nan());
+    assert_eq!(df.get(1, 0), 2.0);
+
+    fs::write(&path, "").unwrap();
+    assert!(matches!(csvfile::read(&path, &CsvOptions::default(), "row"), Err(Error::NoInput(_))));
+}
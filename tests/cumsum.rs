@@ -1,59 +1,52 @@
-use chrono::NaiveDate;
 use light_r::{DataFrame, operations::cumsum};
 
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
 #[test]
 fn test_cumulative_sum() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-    ];
     let data = vec![
         vec![1.0, 2.0, 3.0],
         vec![4.0, 5.0, 6.0],
         vec![7.0, 8.0, 9.0],
     ];
-    let mut df = DataFrame { dates, data };
+    let column_names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let df = DataFrame::from_rows(dates(3), column_names, data, "row").unwrap();
 
-    let result = cumsum::cumulative_sum(&mut df, 10.0);
-    assert_eq!(result.data[0], vec![11.0, 12.0, 13.0]);
-    assert_eq!(result.data[1], vec![15.0, 17.0, 19.0]);
-    assert_eq!(result.data[2], vec![22.0, 25.0, 28.0]);
+    let result = cumsum::cumulative_sum(&df, 10.0).unwrap();
+    assert_eq!(result.row(0), vec![11.0, 12.0, 13.0]);
+    assert_eq!(result.row(1), vec![15.0, 17.0, 19.0]);
+    assert_eq!(result.row(2), vec![22.0, 25.0, 28.0]);
 }
 
 #[test]
 fn test_cumulative_sum_with_nan() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-    ];
     let data = vec![
         vec![1.0, f64::NAN, 3.0],
         vec![4.0, 5.0, 6.0],
         vec![7.0, 8.0, 9.0],
     ];
-    let mut df = DataFrame { dates, data };
+    let column_names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let df = DataFrame::from_rows(dates(3), column_names, data, "column").unwrap();
 
-    let result = cumsum::cumulative_sum(&mut df, 10.0);
-    assert_eq!(result.data.len(), 2);  // NaN row should be removed
-    assert_eq!(result.data[0], vec![14.0, 15.0, 16.0]);
-    assert_eq!(result.data[1], vec![21.0, 23.0, 25.0]);
+    let result = cumsum::cumulative_sum(&df, 10.0).unwrap();
+    assert_eq!(result.num_rows, 2);  // NaN row should be removed
+    assert_eq!(result.index.labels(), vec!["2021-01-02", "2021-01-03"]);
+    assert_eq!(result.row(0), vec![14.0, 15.0, 16.0]);
+    assert_eq!(result.row(1), vec![21.0, 23.0, 25.0]);
 }
 
 #[test]
 fn test_main() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-    ];
     let data = vec![
         vec![1.0, 2.0],
         vec![3.0, 4.0],
     ];
-    let mut df = DataFrame { dates, data };
+    let column_names = vec!["A".to_string(), "B".to_string()];
+    let df = DataFrame::from_rows(dates(2), column_names, data, "row").unwrap();
 
-    let result = cumsum::main(&mut df, 5.0).unwrap();
-    assert_eq!(result.data[0], vec![6.0, 7.0]);
-    assert_eq!(result.data[1], vec![9.0, 11.0]);
+    let result = cumsum::main(&df, 5.0).unwrap();
+    assert_eq!(result.row(0), vec![6.0, 7.0]);
+    assert_eq!(result.row(1), vec![9.0, 11.0]);
 }
@@ -0,0 +1,48 @@
+use approx::assert_relative_eq;
+use light_r::{DataFrame, operations::dlog};
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+fn abc() -> Vec<String> {
+    vec!["A".to_string(), "B".to_string(), "C".to_string()]
+}
+
+fn prices(row_or_column: &str) -> DataFrame {
+    let data = vec![
+        vec![100.0, 50.0, 10.0],
+        vec![110.0, 25.0, -1.0],
+        vec![121.0, 50.0, 10.0],
+    ];
+    DataFrame::from_rows(dates(3), abc(), data, row_or_column).unwrap()
+}
+
+#[test]
+fn test_dlog_in_both_layouts() {
+    for layout in ["row", "column"] {
+        let result = dlog::dlog(&prices(layout)).unwrap();
+
+        assert!(result.row(0).iter().all(|x| x.is_nan()));
+        assert_relative_eq!(result.get(1, 0), (1.1f64).ln(), epsilon = 1e-12);
+        assert_relative_eq!(result.get(1, 1), (0.5f64).ln(), epsilon = 1e-12);
+        assert!(result.get(1, 2).is_nan());
+        assert_relative_eq!(result.get(2, 0), (1.1f64).ln(), epsilon = 1e-12);
+        assert_relative_eq!(result.get(2, 1), (2.0f64).ln(), epsilon = 1e-12);
+        assert!(result.get(2, 2).is_nan());
+    }
+}
+
+#[test]
+fn test_dlog_column_matches_dlog() {
+    for layout in ["row", "column"] {
+        let df = prices(layout);
+        let result = dlog::dlog(&df).unwrap();
+        for col in 0..3 {
+            let mut expected = vec![0.0; 3];
+            dlog::dlog_column(&df.column(col), &mut expected);
+            let actual = result.column(col);
+            assert!(expected.iter().zip(actual.iter()).all(|(x, y)| x == y || (x.is_nan() && y.is_nan())));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::Write;
+
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error};
+use tempfile::NamedTempFile;
+
+fn sample_df() -> DataFrame {
+    DataFrame::from_rows(
+        vec!["2021-01-01".to_string(), "2021-01-02".to_string()],
+        vec!["A".to_string()],
+        vec![vec![1.0], vec![2.0]],
+        "row",
+    )
+    .unwrap()
+}
+
+fn run(engine: &mut Engine, pipeline: &str) -> Error {
+    let pipeline = parse_pipeline(pipeline).unwrap();
+    engine.execute_pipeline(&pipeline, Some(sample_df())).err().unwrap()
+}
+
+#[test]
+fn test_stage_errors_keep_their_kind() {
+    let mut engine = Engine::new();
+
+    let err = run(&mut engine, "dlog | frobnicate");
+    assert!(matches!(err, Error::Stage { stage: 2, .. }));
+    assert!(matches!(err.root(), Error::UnknownCommand(name) if name == "frobnicate"));
+
+    let err = run(&mut engine, "get nothing");
+    assert!(matches!(err.root(), Error::UnknownVariable(name) if name == "nothing"));
+
+    let err = run(&mut engine, "unitscale ten 0.1");
+    assert!(matches!(err.root(), Error::Usage { command, .. } if command == "unitscale"));
+}
+
+#[test]
+fn test_shape_error_from_binary_op() {
+    let mut engine = Engine::new();
+    let columns = vec!["X".to_string(), "Y".to_string()];
+    let other = DataFrame::from_rows(vec!["2021-01-01".to_string()], columns, vec![vec![1.0, 2.0]], "row").unwrap();
+    let two_columns = DataFrame::from_rows(vec!["2021-01-01".to_string()], vec!["A".to_string(), "B".to_string()], vec![vec![1.0, 2.0]], "row").unwrap();
+    engine.set_var("other", other);
+    engine.set_var("two", two_columns);
+    // No column names in common
+    let err = run(&mut engine, "get two | mult other");
+    assert!(matches!(err.root(), Error::Shape(_)));
+}
+
+#[test]
+fn test_load_errors() {
+    let err = DataFrame::new_from_csv("does/not/exist.csv", "row").err().unwrap();
+    assert!(matches!(err, Error::Io(_)));
+
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, "DATE,A\n2021-01-01,1\n2021-01-02,1,2").unwrap();
+    let err = DataFrame::new_from_csv(file.path().to_str().unwrap(), "row").err().unwrap();
+    assert!(matches!(err, Error::Csv { line: 3, .. }));
+}
+
+#[test]
+fn test_parse_error_converts_and_renders() {
+    let err: Error = parse_pipeline("dlog || signal").unwrap_err().into();
+    assert!(matches!(err, Error::Parse(_)));
+    assert!(err.render("dlog || signal").ends_with("      ^"));
+}
+
+#[test]
+fn test_exit_codes_are_distinct() {
+    let errors = [
+        Error::from(parse_pipeline("|").unwrap_err()),
+        Error::Csv { line: 1, message: String::new() },
+        Error::Shape(String::new()),
+        Error::MissingColumn(String::new()),
+        Error::UnknownVariable(String::new()),
+        Error::UnknownCommand(String::new()),
+        Error::Usage { command: String::new(), message: String::new(), usage: String::new() },
+        Error::InvalidValue(String::new()),
+        Error::NoInput(String::new()),
+        Error::Io(std::io::Error::other("disk")),
+        Error::Thread(String::new()),
+        Error::Format(String::new()),
+        Error::Index(String::new()),
+    ];
+    let mut codes: Vec<i32> = errors.iter().map(Error::exit_code).collect();
+    assert!(codes.iter().all(|&code| code > 1));
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), errors.len());
+}
@@ -0,0 +1,152 @@
+use light_r::align::Join;
+use light_r::expr::{BinOp, Expr, Func, Value};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error};
+use approx::assert_relative_eq;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+fn prices() -> DataFrame {
+    let rows = vec![vec![100.0, 50.0], vec![110.0, 40.0], vec![99.0, 50.0]];
+    DataFrame::from_rows(dates(3), vec!["A".to_string(), "B".to_string()], rows, "column").unwrap()
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_var("px", prices());
+    engine
+}
+
+fn eval(engine: &mut Engine, line: &str) -> light_r::Result<DataFrame> {
+    let pipeline = parse_pipeline(line)?;
+    engine.execute_pipeline(&pipeline, None).map(Option::unwrap)
+}
+
+fn failure(engine: &mut Engine, line: &str) -> Error {
+    match eval(engine, line) {
+        Ok(_) => panic!("{} should fail", line),
+        Err(err) => err,
+    }
+}
+
+fn var(name: &str) -> Box<Expr> {
+    Box::new(Expr::Var(name.to_string()))
+}
+
+#[test]
+fn test_precedence() {
+    let expr = Expr::parse("a + b * 2 ^ 3 ^ 2 > -c").unwrap();
+    let power = Expr::Binary(
+        BinOp::Pow,
+        Box::new(Expr::Number(2.0)),
+        Box::new(Expr::Binary(BinOp::Pow, Box::new(Expr::Number(3.0)), Box::new(Expr::Number(2.0)))),
+    );
+    let sum = Expr::Binary(BinOp::Add, var("a"), Box::new(Expr::Binary(BinOp::Mul, var("b"), Box::new(power))));
+    assert_eq!(expr, Expr::Binary(BinOp::Gt, Box::new(sum), Box::new(Expr::Neg(var("c")))));
+
+    // Unary minus binds looser than ^, and names may carry a `$`
+    assert_eq!(
+        Expr::parse("-$x^2").unwrap(),
+        Expr::Neg(Box::new(Expr::Binary(BinOp::Pow, var("x"), Box::new(Expr::Number(2.0)))))
+    );
+    assert_eq!(Expr::parse("1.5e-3").unwrap(), Expr::Number(1.5e-3));
+    assert_eq!(Expr::parse("shift(px)").unwrap(), Expr::Call(Func::Shift, vec![Expr::Var("px".to_string())]));
+}
+
+#[test]
+fn test_scalar_arithmetic() {
+    let lookup = |name: &str| Err(Error::UnknownVariable(name.to_string()));
+    let value = |source: &str| match Expr::parse(source).unwrap().eval(&lookup, Join::Inner).unwrap() {
+        Value::Scalar(x) => x,
+        Value::Frame(_) => panic!("expected a scalar"),
+    };
+    assert_relative_eq!(value("(1 + 2) * 3 - 4 / 8"), 8.5);
+    assert_relative_eq!(value("2 ^ 3 ^ 2"), 512.0);
+    assert_relative_eq!(value("-2 ^ 2"), -4.0);
+    assert_relative_eq!(value("sqrt(16) + abs(-1) + exp(0) + log(exp(2))"), 8.0);
+    assert_eq!(value("sign(-3) + sign(0) * 10 + sign(2) * 100"), 99.0);
+    assert_eq!(value("clip(7, 0, 5) + clip(-7, 0, 5)"), 5.0);
+    assert_eq!(value("(1 < 2) + (2 <= 2) + (3 == 3) + (1 != 1) + (1 > 2) + (1 >= 2)"), 3.0);
+    assert!(value("0 / 0 < 1").is_nan());
+}
+
+#[test]
+fn test_returns_from_prices() {
+    let mut engine = engine();
+    let df = eval(&mut engine, r#"eval "(px / shift(px, 1)) - 1""#).unwrap();
+    assert_eq!(df.column_names, vec!["A", "B"]);
+    assert_eq!(df.index, prices().index);
+    assert!(df.get(0, 0).is_nan());
+    assert_relative_eq!(df.get(1, 0), 0.1, epsilon = 1e-12);
+    assert_relative_eq!(df.get(2, 0), -0.1, epsilon = 1e-12);
+    assert_relative_eq!(df.get(1, 1), -0.2, epsilon = 1e-12);
+    assert_relative_eq!(df.get(2, 1), 0.25, epsilon = 1e-12);
+
+    // The expression may also be given unquoted, and a bare shift lags one period
+    let unquoted = eval(&mut engine, "eval px / shift(px) - 1").unwrap();
+    for col in 0..2 {
+        assert_eq!(unquoted.column(col)[1..], df.column(col)[1..]);
+    }
+}
+
+#[test]
+fn test_functions_and_comparisons_on_frames() {
+    let mut engine = engine();
+    let df = eval(&mut engine, r#"eval "clip(px - 100, -5, 5) * (px > 60)""#).unwrap();
+    assert_eq!(df.column(0).to_vec(), vec![0.0, 5.0, -1.0]);
+    assert_eq!(df.column(1).to_vec(), vec![0.0, 0.0, 0.0]);
+
+    let df = eval(&mut engine, r#"eval "sign(px - 50) + sqrt(px) * 0""#).unwrap();
+    assert_eq!(df.column(1).to_vec(), vec![0.0, -1.0, 0.0]);
+}
+
+#[test]
+fn test_frames_align_by_date() {
+    let mut engine = engine();
+    let weights = DataFrame::from_rows(
+        vec!["2021-01-01".to_string(), "2021-01-03".to_string()],
+        vec!["A".to_string(), "B".to_string()],
+        vec![vec![1.0, 2.0], vec![3.0, 4.0]],
+        "row",
+    )
+    .unwrap();
+    engine.set_var("w", weights);
+
+    let inner = eval(&mut engine, r#"eval "px * w""#).unwrap();
+    assert_eq!(inner.index.labels(), vec!["2021-01-01", "2021-01-03"]);
+    assert_eq!(inner.row(1), vec![297.0, 200.0]);
+
+    let asof = eval(&mut engine, r#"eval "px * w" join=asof"#).unwrap();
+    assert_eq!(asof.num_rows, 3);
+    assert_eq!(asof.row(1), vec![110.0, 80.0]);
+}
+
+#[test]
+fn test_current_frame_is_underscore() {
+    let mut engine = engine();
+    let df = eval(&mut engine, r#"get px | eval "_ * 2 + px""#).unwrap();
+    assert_eq!(df.row(0), vec![300.0, 150.0]);
+    assert!(matches!(failure(&mut engine, r#"eval "_ + 1""#).root(), Error::NoInput(_)));
+}
+
+#[test]
+fn test_errors() {
+    let mut engine = engine();
+    assert!(matches!(failure(&mut engine, r#"eval "nope + 1""#).root(), Error::UnknownVariable(_)));
+    assert!(matches!(failure(&mut engine, r#"eval "1 + 2""#).root(), Error::InvalidValue(_)));
+    assert!(matches!(failure(&mut engine, "eval").root(), Error::Usage { .. }));
+    assert!(matches!(failure(&mut engine, r#"eval "px" join=sideways"#).root(), Error::InvalidValue(_)));
+    assert!(matches!(failure(&mut engine, r#"eval "clip(px, 1, px)""#).root(), Error::InvalidValue(_)));
+    assert!(matches!(failure(&mut engine, r#"eval "shift(px, 0.5)""#).root(), Error::InvalidValue(_)));
+
+    for (source, column) in [("px +", 5), ("(px", 4), ("px ) 1", 4), ("px # 2", 4), ("foo(px)", 1), ("abs(px, 2)", 1)] {
+        match Expr::parse(source) {
+            Err(Error::InvalidValue(message)) => {
+                assert!(message.contains(&format!("column {}", column)), "{}: {}", source, message)
+            }
+            other => panic!("{} should not parse: {:?}", source, other),
+        }
+    }
+}
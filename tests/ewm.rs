@@ -0,0 +1,205 @@
+use light_r::align::Join;
+use light_r::operations::ewm::{self, Decay, Ewm};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error};
+use approx::assert_relative_eq;
+use proptest::prelude::*;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+fn frame(columns: &[(&str, &[f64])], row_or_column: &str) -> DataFrame {
+    let n = columns[0].1.len();
+    let rows = (0..n).map(|i| columns.iter().map(|(_, values)| values[i]).collect()).collect();
+    let names = columns.iter().map(|(name, _)| name.to_string()).collect();
+    DataFrame::from_rows(dates(n), names, rows, row_or_column).unwrap()
+}
+
+fn assert_values(actual: &[f64], expected: &[f64], epsilon: f64) {
+    assert_eq!(actual.len(), expected.len());
+    for (i, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+        assert!((a.is_nan() && e.is_nan()) || (a - e).abs() <= epsilon, "row {}: {} vs {}", i, a, e);
+    }
+}
+
+/// The example series of pandas' `DataFrame.ewm` documentation
+const B: &[f64] = &[0.0, 1.0, 2.0, f64::NAN, 4.0];
+
+#[test]
+fn test_mean_matches_pandas_documentation() {
+    let df = frame(&[("B", B)], "column");
+    let com = Ewm::new(Decay::Com(0.5)).unwrap();
+
+    // df.ewm(com=0.5).mean()
+    let mean = ewm::ema(&df, &com).unwrap();
+    assert_values(&mean.column(0), &[0.0, 0.75, 1.615385, 1.615385, 3.670213], 1e-6);
+
+    // df.ewm(com=0.5, ignore_na=True).mean()
+    let ignore_na = ewm::ema(&df, &Ewm { ignore_na: true, ..com }).unwrap();
+    assert_values(&ignore_na.column(0), &[0.0, 0.75, 1.615385, 1.615385, 3.225], 1e-6);
+
+    // df.ewm(com=0.5, adjust=False).mean(): y = y / 3 + 2x / 3, with the gap decaying y twice
+    let recursive = ewm::ema(&df, &Ewm { adjust: false, ..com }).unwrap();
+    assert_values(&recursive.column(0), &[0.0, 2.0 / 3.0, 14.0 / 9.0, 14.0 / 9.0, 3.650794], 1e-6);
+}
+
+#[test]
+fn test_decay_parameters() {
+    assert_relative_eq!(Decay::Span(9.0).alpha().unwrap(), 0.2);
+    assert_relative_eq!(Decay::Com(4.0).alpha().unwrap(), 0.2);
+    assert_relative_eq!(Decay::Halflife(1.0).alpha().unwrap(), 0.5);
+    assert_relative_eq!(Decay::Alpha(0.3).alpha().unwrap(), 0.3);
+    for bad in [Decay::Span(0.5), Decay::Com(-1.0), Decay::Halflife(0.0), Decay::Alpha(0.0), Decay::Alpha(1.5)] {
+        assert!(matches!(bad.alpha(), Err(Error::InvalidValue(_))), "{:?}", bad);
+    }
+    assert!(matches!(Decay::parse("window", 3.0), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_vol_of_two_values_is_their_sample_std() {
+    // With two values the bias correction undoes the weighting
+    let df = frame(&[("A", &[0.0, 1.0, f64::NAN])], "row");
+    let vol = ewm::ewvol(&df, &Ewm::new(Decay::Alpha(2.0 / 3.0)).unwrap(), false).unwrap();
+    assert_values(&vol.column(0), &[f64::NAN, 0.5f64.sqrt(), 0.5f64.sqrt()], 1e-12);
+    let biased = ewm::ewvol(&df, &Ewm::new(Decay::Alpha(2.0 / 3.0)).unwrap(), true).unwrap();
+    assert_values(&biased.column(0), &[0.0, 0.1875f64.sqrt(), 0.1875f64.sqrt()], 1e-12);
+}
+
+#[test]
+fn test_min_periods() {
+    let df = frame(&[("B", B)], "column");
+    let options = Ewm { min_periods: 3, ..Ewm::new(Decay::Com(0.5)).unwrap() };
+    let mean = ewm::ema(&df, &options).unwrap();
+    assert_values(&mean.column(0), &[f64::NAN, f64::NAN, 1.615385, 1.615385, 3.670213], 1e-6);
+}
+
+#[test]
+fn test_correlation() {
+    let x: &[f64] = &[1.0, 3.0, 2.0, 5.0, f64::NAN, 4.0, 7.0];
+    let df = frame(&[("A", x), ("B", &x.iter().map(|v| -2.0 * v).collect::<Vec<_>>())], "column");
+    let options = Ewm::new(Decay::Span(3.0)).unwrap();
+    let own = ewm::ewcorr(&df, &df, &options, Join::Inner).unwrap();
+    assert!(own.get(0, 0).is_nan());
+    assert_values(&own.column(0)[1..], &[1.0; 6], 1e-12);
+
+    // A single-column frame is correlated with every column
+    let a = df.select_columns(&[0]);
+    let against = ewm::ewcorr(&df, &a, &options, Join::Inner).unwrap();
+    assert_values(&against.column(1)[1..], &[-1.0; 6], 1e-12);
+
+    // Constant series have no correlation
+    let flat = frame(&[("A", &[1.0; 7])], "column");
+    assert!(ewm::ewcorr(&df, &flat, &options, Join::Inner).unwrap().column(0).iter().all(|c| c.is_nan()));
+}
+
+#[test]
+fn test_covariance_aligns_dates() {
+    let x = frame(&[("A", &[1.0, 2.0, 4.0, 8.0])], "row");
+    let y = DataFrame::from_rows(
+        vec!["2021-01-02".to_string(), "2021-01-04".to_string()],
+        vec!["A".to_string()],
+        vec![vec![1.0], vec![3.0]],
+        "row",
+    )
+    .unwrap();
+    let options = Ewm::new(Decay::Alpha(0.5)).unwrap();
+    let inner = ewm::ewcov(&x, &y, &options, false, Join::Inner).unwrap();
+    assert_eq!(inner.index.labels(), vec!["2021-01-02", "2021-01-04"]);
+    // Two pairs: the sample covariance of (2, 8) and (1, 3)
+    assert_values(&inner.column(0), &[f64::NAN, 6.0], 1e-12);
+
+    let left = ewm::ewcov(&x, &y, &options, false, Join::Left).unwrap();
+    assert_eq!(left.num_rows, 4);
+    assert!(left.get(2, 0).is_nan());
+}
+
+#[test]
+fn test_ewm_verbs_in_a_pipeline() {
+    let mut engine = Engine::new();
+    engine.set_var("b", frame(&[("B", B)], "column"));
+    let mut run = |line: &str| engine.execute_pipeline(&parse_pipeline(line).unwrap(), None);
+
+    let mean = run("get b | ema com 0.5 ignore_na=true").unwrap().unwrap();
+    assert_relative_eq!(mean.get(4, 0), 3.225, epsilon = 1e-9);
+    let vol = run("get b | ewvol halflife 2 bias=true").unwrap().unwrap();
+    assert_eq!(vol.get(0, 0), 0.0);
+    let corr = run("get b | ewcorr b span 5").unwrap().unwrap();
+    assert_relative_eq!(corr.get(4, 0), 1.0, epsilon = 1e-12);
+    let cov = run("get b | ewcov b alpha 0.5 min_periods=2").unwrap().unwrap();
+    assert!(cov.get(0, 0).is_nan());
+
+    assert!(matches!(run("get b | ema span 0").err().unwrap().root(), Error::InvalidValue(_)));
+    assert!(matches!(run("get b | ema span 3 adjust=maybe").err().unwrap().root(), Error::InvalidValue(_)));
+}
+
+/// Explicit weights on each value, summed directly. Weights decay by (1 - alpha) per row, or
+/// per value with `ignore_na`; without `adjust` a new value weighs alpha and the weights are
+/// renormalised to sum to one, as in pandas. Also returns the bias correction of each row, by
+/// which it magnifies rounding error.
+fn reference(x: &[f64], y: &[f64], options: &Ewm, bias: bool) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let (mut means, mut covs, mut corrections) = (Vec::new(), Vec::new(), Vec::new());
+    let mut weights: Vec<(usize, f64)> = Vec::new();
+    for t in 0..x.len() {
+        let observed = !x[t].is_nan() && !y[t].is_nan();
+        if observed || !options.ignore_na {
+            weights.iter_mut().for_each(|(_, w)| *w *= 1.0 - options.alpha);
+        }
+        if observed {
+            weights.push((t, if options.adjust || weights.is_empty() { 1.0 } else { options.alpha }));
+            if !options.adjust {
+                let total: f64 = weights.iter().map(|(_, w)| w).sum();
+                weights.iter_mut().for_each(|(_, w)| *w /= total);
+            }
+        }
+        if weights.len() < options.min_periods.max(1) {
+            means.push(f64::NAN);
+            covs.push(f64::NAN);
+            corrections.push(1.0);
+            continue;
+        }
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        let mean = |v: &[f64]| weights.iter().map(|&(i, w)| w * v[i]).sum::<f64>() / total;
+        let (mean_x, mean_y) = (mean(x), mean(y));
+        let cov = weights.iter().map(|&(i, w)| w * (x[i] - mean_x) * (y[i] - mean_y)).sum::<f64>() / total;
+        let squares: f64 = weights.iter().map(|(_, w)| w * w).sum();
+        let correction = if bias { 1.0 } else { total * total / (total * total - squares) };
+        means.push(mean_x);
+        covs.push(if bias || (weights.len() > 1 && correction.is_finite()) { cov * correction } else { f64::NAN });
+        corrections.push(if correction.is_finite() { correction } else { 1.0 });
+    }
+    (means, covs, corrections)
+}
+
+proptest! {
+    #[test]
+    fn kernels_match_the_weighted_definition(
+        pairs in prop::collection::vec(
+            (prop_oneof![1 => Just(f64::NAN), 4 => -100.0..100.0f64], prop_oneof![1 => Just(f64::NAN), 4 => -100.0..100.0f64]),
+            0..40,
+        ),
+        alpha in 0.05..1.0f64,
+        adjust: bool,
+        ignore_na: bool,
+        bias: bool,
+        min_periods in 0usize..4,
+    ) {
+        let (x, y): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+        let options = Ewm { alpha, adjust, ignore_na, min_periods };
+        let close = |a: f64, b: f64, correction: f64| (a.is_nan() && b.is_nan()) || (a - b).abs() <= 1e-9 * correction * (1.0 + b.abs());
+
+        let mut cov = vec![0.0; x.len()];
+        ewm::cov_columns(&x, &y, &mut cov, &options, bias);
+        let (_, expected, corrections) = reference(&x, &y, &options, bias);
+        for (i, ((&a, &b), &correction)) in cov.iter().zip(&expected).zip(&corrections).enumerate() {
+            prop_assert!(close(a, b, correction), "cov at row {}: {} vs {}", i, a, b);
+        }
+
+        let mut mean = vec![0.0; x.len()];
+        ewm::mean_column(&x, &mut mean, &options);
+        let (expected, _, _) = reference(&x, &x, &options, bias);
+        for (i, (&a, &b)) in mean.iter().zip(&expected).enumerate() {
+            prop_assert!(close(a, b, 1.0), "mean at row {}: {} vs {}", i, a, b);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use light_r::registry::{describe, signature, ArgKind, ArgSpec};
+use light_r::Engine;
+
+#[test]
+fn test_signature_marks_required_default_and_variadic() {
+    let engine = Engine::new();
+    let registry = engine.registry();
+    assert_eq!(signature(registry.get("unitscale").unwrap()), "unitscale <window:count> <target_vol:number>");
+    assert_eq!(signature(registry.get("momentum").unwrap()), "momentum <lookback:count> [frequency:count=1]");
+    assert_eq!(signature(registry.get("cgrep").unwrap()), "cgrep <columns:text>...");
+    assert_eq!(signature(registry.get("mult").unwrap()), "mult <x:variable> [y:variable] [join:text=inner]");
+}
+
+#[test]
+fn test_describe_lists_arguments() {
+    let engine = Engine::new();
+    let text = describe(engine.registry().get("shift").unwrap());
+    assert!(text.starts_with("Usage: shift [periods:integer=1]"));
+    assert!(text.contains("periods"));
+    assert!(text.contains("default 1"));
+}
+
+#[test]
+fn test_help_overview_lists_every_command() {
+    let engine = Engine::new();
+    let text = engine.help(None).unwrap();
+    for name in engine.command_names() {
+        assert!(text.contains(&format!("  {}", name)), "missing {} in help", name);
+    }
+}
+
+#[test]
+fn test_help_for_one_command() {
+    let engine = Engine::new();
+    assert!(engine.help(Some("unitscale")).unwrap().contains("target_vol"));
+    assert!(engine.help(Some("get")).unwrap().starts_with("Usage: get <name:variable>"));
+    assert!(engine.help(Some("frobnicate")).is_err());
+}
+
+#[test]
+fn test_validate_by_kind() {
+    assert!(ArgSpec::required("n", ArgKind::Count).validate("3").is_ok());
+    assert!(ArgSpec::required("n", ArgKind::Count).validate("-3").is_err());
+    assert!(ArgSpec::required("n", ArgKind::Integer).validate("-3").is_ok());
+    assert!(ArgSpec::required("x", ArgKind::Number).validate("0.5").is_ok());
+    assert!(ArgSpec::required("x", ArgKind::Number).validate("half").is_err());
+    assert!(ArgSpec::required("d", ArgKind::Date).validate("2021-02-30").is_err());
+    assert!(ArgSpec::required("d", ArgKind::Date).validate("2021-02-28").is_ok());
+}
+
+#[test]
+fn test_errors_quote_the_signature() {
+    let mut engine = Engine::new();
+    let df = light_r::DataFrame::from_rows(
+        vec!["2021-01-01".to_string()],
+        vec!["A".to_string()],
+        vec![vec![1.0]],
+        "row",
+    )
+    .unwrap();
+
+    let err = engine.execute_command("unitscale", &["20".to_string()], Some(df.clone())).err().unwrap();
+    assert!(err.to_string().contains("missing argument <target_vol:number>"));
+    assert!(err.to_string().contains("usage: unitscale <window:count> <target_vol:number>"));
+
+    let err = engine.execute_command("after", &["yesterday".to_string()], Some(df)).err().unwrap();
+    assert!(err.to_string().contains("invalid value 'yesterday' for <date:date>"));
+    assert!(err.to_string().contains("usage: after <date:date>"));
+}
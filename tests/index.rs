@@ -0,0 +1,112 @@
+use chrono::{FixedOffset, NaiveDate};
+use light_r::dataframe::CsvOptions;
+use light_r::operations::{after, before, load_bin, save_bin};
+use light_r::{DataFrame, Engine, Error, Index, IndexFormat};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn labels(labels: &[&str]) -> Vec<String> {
+    labels.iter().map(|label| label.to_string()).collect()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_inferred_index_types() {
+    let dates = Index::from(labels(&["2021-01-01", "2021-01-04"]));
+    assert_eq!(dates, Index::Dates(vec![date(2021, 1, 1), date(2021, 1, 4)]));
+
+    let times = Index::from(labels(&["2021-01-01T09:30:00", "2021-01-01 16:00:00.25"]));
+    assert!(matches!(times, Index::Timestamps(_, None)));
+    assert_eq!(times.labels(), vec!["2021-01-01 09:30:00", "2021-01-01 16:00:00.250"]);
+
+    let zoned = Index::from(labels(&["2021-01-01T09:30:00+01:00", "2021-01-01 10:30:00+01:00"]));
+    assert!(matches!(zoned, Index::Timestamps(_, Some(offset)) if offset == FixedOffset::east_opt(3600).unwrap()));
+    assert_eq!(zoned.label(0), "2021-01-01 09:30:00+01:00");
+
+    // Different offsets are brought to UTC
+    let mixed = Index::from(labels(&["2021-01-01T09:30:00+01:00", "2021-01-01T09:30:00Z"]));
+    assert_eq!(mixed.labels(), vec!["2021-01-01 08:30:00+00:00", "2021-01-01 09:30:00+00:00"]);
+
+    let tickers = Index::from(labels(&["ES", "NQ"]));
+    assert_eq!(tickers, Index::Labels(labels(&["ES", "NQ"])));
+    let half_zoned = Index::from(labels(&["2021-01-01T09:30:00+01:00", "2021-01-01T10:30:00"]));
+    assert!(!half_zoned.is_temporal());
+}
+
+#[test]
+fn test_custom_formats() {
+    let format: IndexFormat = "%d/%m/%Y".parse().unwrap();
+    let index = Index::parse(labels(&["04/01/2021", "05/01/2021"]), &format).unwrap();
+    assert_eq!(index, Index::Dates(vec![date(2021, 1, 4), date(2021, 1, 5)]));
+
+    let err = Index::parse(labels(&["2021-01-04"]), &format).err().unwrap();
+    assert!(matches!(err, Error::InvalidValue(_)));
+
+    let intraday = Index::parse(labels(&["20210104 0930"]), &"%Y%m%d %H%M".parse().unwrap()).unwrap();
+    assert_eq!(intraday.labels(), vec!["2021-01-04 09:30:00"]);
+
+    let kept = Index::parse(labels(&["2021-01-04"]), &IndexFormat::Labels).unwrap();
+    assert!(!kept.is_temporal());
+}
+
+#[test]
+fn test_validation() {
+    assert!(Index::from(labels(&["2021-01-01", "2021-01-02"])).validate().is_ok());
+    let duplicate = Index::from(labels(&["2021-01-01", "2021-01-02", "2021-01-02"])).validate();
+    assert!(matches!(duplicate, Err(Error::Index(message)) if message.contains("row 3")));
+    let unsorted = Index::from(labels(&["2021-01-02", "2021-01-01"])).validate();
+    assert!(matches!(unsorted, Err(Error::Index(_))));
+    assert!(Index::from(labels(&["ES", "ES"])).validate().is_ok());
+}
+
+#[test]
+fn test_loaders_reject_unsorted_dates() {
+    let csv = "DATE,A\n2021-01-02,1\n2021-01-01,2\n";
+    let err = DataFrame::from_csv_reader(csv.as_bytes(), &CsvOptions::default(), "row").err().unwrap();
+    assert!(matches!(err, Error::Index(_)));
+    assert_eq!(err.exit_code(), 14);
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{}", csv).unwrap();
+    let err = DataFrame::new_from_csv(file.path().to_str().unwrap(), "column").err().unwrap();
+    assert!(matches!(err, Error::Index(_)));
+}
+
+#[test]
+fn test_load_verb_format_argument() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "DATE,A\n31/12/2020,1\n04/01/2021,2\n").unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+
+    let mut engine = Engine::new();
+    let args = vec![path, ",".to_string(), "0".to_string(), "%d/%m/%Y".to_string()];
+    let df = engine.execute_command("load", &args, None).ok().unwrap();
+    assert_eq!(df.index, Index::Dates(vec![date(2020, 12, 31), date(2021, 1, 4)]));
+}
+
+#[test]
+fn test_date_slicing_on_timestamps() {
+    let index = labels(&["2021-01-01 09:30:00", "2021-01-01 16:00:00", "2021-01-02 09:30:00"]);
+    let df = DataFrame::from_rows(index, labels(&["A"]), vec![vec![1.0], vec![2.0], vec![3.0]], "column").unwrap();
+
+    let later = after::after_date(&df, date(2021, 1, 1)).unwrap();
+    assert_eq!(later.index.labels(), vec!["2021-01-02 09:30:00"]);
+    let earlier = before::before_date(&df, date(2021, 1, 2)).unwrap();
+    assert_eq!(earlier.num_rows, 2);
+
+    let tickers = DataFrame::from_rows(labels(&["ES"]), labels(&["A"]), vec![vec![1.0]], "row").unwrap();
+    assert!(matches!(after::after_date(&tickers, date(2021, 1, 1)), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_binary_file_keeps_zoned_timestamps() {
+    let index = labels(&["2021-01-01T09:30:00-05:00", "2021-01-01T16:00:00-05:00"]);
+    let df = DataFrame::from_rows(index, labels(&["A"]), vec![vec![1.0], vec![2.0]], "row").unwrap();
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    save_bin::save_bin(&df, path).unwrap();
+    assert_eq!(load_bin::load_bin(path).unwrap().index, df.index);
+}
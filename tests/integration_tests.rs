@@ -0,0 +1,144 @@
+use light_r::{DataFrame, Engine};
+
+fn sample_df() -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string(), "2021-01-03".to_string()];
+    let data = vec![
+        vec![1.0, -2.0],
+        vec![-3.0, 4.0],
+        vec![5.0, 6.0],
+    ];
+    let column_names = vec!["A".to_string(), "B".to_string()];
+    DataFrame::from_rows(dates, column_names, data, "column").unwrap()
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+#[test]
+fn test_bind_and_mult() {
+    let mut engine = Engine::new();
+
+    let df = engine.execute_command("->", &args(&["px"]), Some(sample_df())).unwrap();
+    let df = engine.execute_command("signal", &[], Some(df)).unwrap();
+    let result = engine.execute_command("mult", &args(&["px"]), Some(df)).unwrap();
+
+    assert_eq!(result.row(0), vec![1.0, 2.0]);
+    assert_eq!(result.row(1), vec![3.0, 4.0]);
+    assert_eq!(result.row(2), vec![5.0, 6.0]);
+}
+
+#[test]
+fn test_mult_unknown_variable() {
+    let mut engine = Engine::new();
+    let result = engine.execute_command("mult", &args(&["missing"]), Some(sample_df()));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_legacy_verbs_chain() {
+    let mut engine = Engine::new();
+
+    let df = engine.execute_command("after", &args(&["2021-01-01"]), Some(sample_df())).unwrap();
+    let df = engine.execute_command("cgrep", &args(&["B"]), Some(df)).unwrap();
+    let df = engine.execute_command("shift", &args(&["1"]), Some(df)).unwrap();
+    let df = engine.execute_command("ffill", &[], Some(df)).unwrap();
+    let result = engine.execute_command("cumsum", &args(&["0"]), Some(df)).unwrap();
+
+    assert_eq!(result.column_names, vec!["B"]);
+    assert_eq!(result.index.labels(), vec!["2021-01-03"]);
+    assert_eq!(result.get(0, 0), 4.0);
+}
+
+#[test]
+fn test_get_recalls_variable() {
+    let mut engine = Engine::new();
+
+    let df = engine.execute_command("->", &args(&["px"]), Some(sample_df())).unwrap();
+    let df = engine.execute_command("signal", &[], Some(df)).unwrap();
+    let result = engine.execute_command("get", &args(&["px"]), Some(df)).unwrap();
+
+    assert_eq!(result.row(0), vec![1.0, -2.0]);
+    assert!(engine.execute_command("get", &args(&["missing"]), None).is_err());
+}
+
+#[test]
+fn test_binary_ops_between_variables() {
+    let mut engine = Engine::new();
+
+    let df = engine.execute_command("->", &args(&["px"]), Some(sample_df())).unwrap();
+    let df = engine.execute_command("signal", &[], Some(df)).unwrap();
+    let df = engine.execute_command("->", &args(&["sig"]), Some(df)).unwrap();
+
+    let result = engine.execute_command("add", &args(&["px", "sig"]), Some(df.clone())).unwrap();
+    assert_eq!(result.row(0), vec![2.0, -3.0]);
+
+    let result = engine.execute_command("sub", &args(&["sig"]), Some(sample_df())).unwrap();
+    assert_eq!(result.row(1), vec![-2.0, 3.0]);
+
+    let result = engine.execute_command("div", &args(&["px", "px"]), Some(df)).unwrap();
+    assert_eq!(result.row(2), vec![1.0, 1.0]);
+
+    assert!(engine.execute_command("mult", &[], Some(sample_df())).is_err());
+}
+
+#[test]
+fn test_drop_variable() {
+    let mut engine = Engine::new();
+
+    let df = engine.execute_command("->", &args(&["a"]), Some(sample_df())).unwrap();
+    let df = engine.execute_command("->", &args(&["b"]), Some(df)).unwrap();
+    assert_eq!(engine.var_names(), vec!["a", "b"]);
+
+    let df = engine.execute_command("drop", &args(&["a"]), Some(df)).unwrap();
+    assert_eq!(engine.var_names(), vec!["b"]);
+    assert!(engine.execute_command("drop", &args(&["a"]), Some(df)).is_err());
+}
+
+#[test]
+fn test_unknown_command() {
+    let mut engine = Engine::new();
+    assert!(engine.execute_command("frobnicate", &[], Some(sample_df())).is_err());
+}
+
+#[test]
+fn test_arguments_by_name() {
+    let mut engine = Engine::new();
+    let by_position = engine.execute_command("momentum", &args(&["1", "2"]), Some(sample_df())).unwrap();
+    let by_name = engine.execute_command("momentum", &args(&["frequency=2", "1"]), Some(sample_df())).unwrap();
+    assert_eq!(by_name.index, by_position.index);
+    assert_eq!(by_name.values(), by_position.values());
+    assert!(engine.execute_command("shift", &args(&["periods=one"]), Some(sample_df())).is_err());
+}
+
+#[test]
+fn test_bad_argument() {
+    let mut engine = Engine::new();
+    assert!(engine.execute_command("shift", &args(&["one"]), Some(sample_df())).is_err());
+    assert!(engine.execute_command("momentum", &[], Some(sample_df())).is_err());
+    assert!(engine.execute_command("shift", &args(&["1", "2"]), Some(sample_df())).is_err());
+}
+
+#[test]
+fn test_script_carries_variables_between_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let csv_path = dir.path().join("px.csv");
+    std::fs::write(&csv_path, "DATE,A,B\n2021-01-01,1,-2\n2021-01-02,-3,4\n").unwrap();
+
+    let script = format!(
+        "# bind prices, then build a signal from them\nload \"{}\" | -> px\nsignal | -> sig\n\nget px | mult sig | -> abs\n",
+        csv_path.display()
+    );
+    let mut engine = Engine::new();
+    engine.process_script(&script).unwrap();
+
+    assert_eq!(engine.var_names(), vec!["abs", "px", "sig"]);
+    assert_eq!(engine.var("abs").unwrap().row(1), vec![3.0, 4.0]);
+}
+
+#[test]
+fn test_script_reports_failing_line() {
+    let mut engine = Engine::new();
+    let err = engine.process_script("load missing.csv\n").unwrap_err();
+    assert!(err.to_string().starts_with("line 1, column 1 (stage 1, `load missing.csv`)"));
+}
@@ -0,0 +1,169 @@
+use light_r::{DataFrame, DataFrameBuilder, Engine};
+use proptest::prelude::*;
+
+/// Arguments for each operation when run over an arbitrary frame; side-effecting verbs are skipped
+const OP_ARGS: &[(&str, &[&str])] = &[
+    ("after", &["2021-01-02"]),
+    ("before", &["2021-01-04"]),
+    ("slice", &["between", "2021-01-02", "2021-01-05"]),
+    ("cgrep", &["C1", "C0"]),
+    ("rename", &["C0", "X"]),
+    ("cumsum", &["100"]),
+    ("dlog", &[]),
+    ("dlog_multithread", &[]),
+    ("ewa", &[]),
+    ("xrank", &["pct=true"]),
+    ("xzscore", &[]),
+    ("xdemean", &[]),
+    ("xwinsor", &["0.25"]),
+    ("xnormalize", &[]),
+    ("xtopn", &["-1"]),
+    ("ema", &["span", "3"]),
+    ("ewvol", &["halflife", "2", "adjust=false"]),
+    ("ewcov", &["other", "alpha", "0.5"]),
+    ("ewcorr", &["other", "com", "1", "ignore_na=true"]),
+    ("ffill", &[]),
+    ("momentum", &["1", "1"]),
+    ("shift", &["-1"]),
+    ("signal", &[]),
+    ("unitscale", &["2", "0.1"]),
+    ("rmean", &["3", "1"]),
+    ("rstd", &["3", "2"]),
+    ("rsum", &["2"]),
+    ("rmin", &["3", "1"]),
+    ("rmax", &["3", "1"]),
+    ("rmedian", &["3", "1"]),
+    ("rquantile", &["3", "0.9", "1"]),
+    ("rskew", &["3"]),
+    ("rzscore", &["3", "2"]),
+    ("mult", &["other"]),
+    ("add", &["other"]),
+    ("sub", &["other"]),
+    ("div", &["other"]),
+];
+const SKIPPED: &[&str] = &["load", "save", "load_bin", "save_bin", "plot", "print", "head", "tags"];
+
+fn frame(rows: &[Vec<f64>], row_or_column: &str) -> DataFrame {
+    let dates: Vec<String> = (0..rows.len()).map(|i| format!("2021-01-{:02}", i + 1)).collect();
+    let columns = (0..rows[0].len()).map(|i| format!("C{}", i)).collect();
+    DataFrame::from_rows(dates, columns, rows.to_vec(), row_or_column).unwrap()
+}
+
+/// Equal values, counting NaN as equal to NaN
+fn same_values(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x == y || (x.is_nan() && y.is_nan()))
+}
+
+fn assert_same(a: &DataFrame, b: &DataFrame) {
+    assert_eq!(a.index, b.index);
+    assert_eq!(a.column_names, b.column_names);
+    for row in 0..a.num_rows {
+        assert!(same_values(&a.row(row), &b.row(row)), "row {}: {:?} vs {:?}", row, a.row(row), b.row(row));
+    }
+}
+
+fn cell() -> impl Strategy<Value = f64> {
+    prop_oneof![8 => -50.0..150.0f64, 1 => Just(f64::NAN), 1 => Just(0.0)]
+}
+
+fn rows() -> impl Strategy<Value = Vec<Vec<f64>>> {
+    (1..9usize, 2..5usize).prop_flat_map(|(n, m)| prop::collection::vec(prop::collection::vec(cell(), m), n))
+}
+
+#[test]
+fn test_every_op_is_covered() {
+    for name in Engine::new().registry().names() {
+        let known = OP_ARGS.iter().any(|(op, _)| *op == name) || SKIPPED.contains(&name);
+        assert!(known, "add {} to OP_ARGS so it is checked in both layouts", name);
+    }
+}
+
+#[test]
+fn test_builder_and_accessors_agree() {
+    for layout in ["row", "column"] {
+        let names = |prefix: &str, n: usize| (0..n).map(|i| format!("{}{}", prefix, i)).collect();
+        let mut builder = DataFrameBuilder::new(names("r", 3), names("c", 4), layout).unwrap();
+        for row in 0..3 {
+            for col in 0..4 {
+                builder.set(row, col, (row * 10 + col) as f64);
+            }
+        }
+        let df = builder.build().unwrap();
+        assert_eq!(df.get(2, 3), 23.0);
+        assert_eq!(df.row(1), vec![10.0, 11.0, 12.0, 13.0]);
+        assert_eq!(df.column(2).to_vec(), vec![2.0, 12.0, 22.0]);
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_ops_agree_across_layouts(rows in rows()) {
+        let by_row = frame(&rows, "row");
+        let by_column = frame(&rows, "column");
+        for column in 0..by_row.num_columns {
+            prop_assert!(same_values(&by_row.column(column), &by_column.column(column)));
+        }
+
+        for (op, args) in OP_ARGS {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let mut results = Vec::new();
+            for df in [&by_row, &by_column] {
+                let mut engine = Engine::new();
+                engine.set_var("other", df.clone());
+                results.push(engine.execute_command(op, &args, Some(df.clone())).ok());
+            }
+            match (&results[0], &results[1]) {
+                (Some(a), Some(b)) => assert_same(a, b),
+                (None, None) => {}
+                _ => panic!("{} succeeded in only one layout", op),
+            }
+        }
+    }
+
+    #[test]
+    fn prop_ops_agree_on_views_and_copies(rows in rows(), layout in prop_oneof![Just("row"), Just("column")]) {
+        // Drop the first row and reverse the columns, so the view is strided and reordered
+        let df = frame(&rows, layout);
+        let reversed: Vec<usize> = (0..df.num_columns).rev().collect();
+        let view = df.slice_rows(1..df.num_rows).select_columns(&reversed);
+        let copy = DataFrame::from_rows(
+            view.index.clone(),
+            view.column_names.clone(),
+            (0..view.num_rows).map(|row| view.row(row)).collect(),
+            layout,
+        ).unwrap();
+        prop_assert!(same_values(&view.values(), &copy.values()));
+
+        for (op, args) in OP_ARGS {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            let mut results = Vec::new();
+            for df in [&view, &copy] {
+                let mut engine = Engine::new();
+                engine.set_var("other", df.clone());
+                results.push(engine.execute_command(op, &args, Some(df.clone())).ok());
+            }
+            match (&results[0], &results[1]) {
+                (Some(a), Some(b)) => assert_same(a, b),
+                (None, None) => {}
+                _ => panic!("{} succeeded on only one of a view and a copy", op),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_column_slices_borrow_the_mapping() {
+    use std::borrow::Cow;
+
+    let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]];
+    let by_column = frame(&rows, "column");
+    assert!(matches!(by_column.column(1), Cow::Borrowed(&[2.0, 4.0, 6.0])));
+    assert_eq!(&*by_column.values(), &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]);
+
+    let by_row = frame(&rows, "row");
+    assert!(matches!(by_row.column(1), Cow::Owned(_)));
+    assert_eq!(&*by_row.values(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let doubled = by_column.map(|x| x * 2.0).unwrap();
+    assert_eq!(doubled.row(2), vec![10.0, 12.0]);
+}
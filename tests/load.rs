@@ -1,5 +1,6 @@
-use light_r::{DataFrame, operations::load};
-use chrono::NaiveDate;
+use light_r::dataframe::{CsvOptions, IndexColumn};
+use light_r::operations::load;
+use light_r::{DataFrame, DataFrameBuilder, Engine, Error};
 use tempfile::NamedTempFile;
 use std::io::Write;
 
@@ -11,23 +12,22 @@ fn test_load() {
 2021-01-03,5.0,6.0
 ";
 
-    let temp_file = NamedTempFile::new().unwrap();
+    let mut temp_file = NamedTempFile::new().unwrap();
     write!(temp_file.as_file_mut(), "{}", csv_content).unwrap();
 
     let result = load::load(temp_file.path().to_str().unwrap()).unwrap();
     
-    assert_eq!(result.dates.len(), 3);
-    assert_eq!(result.data.len(), 3);
+    assert_eq!(result.num_rows, 3);
     assert_eq!(result.column_names, vec!["Column1", "Column2"]);
 
-    assert_eq!(result.dates[0], NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
-    assert_eq!(result.data[0], vec![1.0, 2.0]);
+    assert_eq!(result.index.label(0), "2021-01-01");
+    assert_eq!(result.row(0), vec![1.0, 2.0]);
 
-    assert_eq!(result.dates[1], NaiveDate::from_ymd_opt(2021, 1, 2).unwrap());
-    assert_eq!(result.data[1], vec![3.0, 4.0]);
+    assert_eq!(result.index.label(1), "2021-01-02");
+    assert_eq!(result.row(1), vec![3.0, 4.0]);
 
-    assert_eq!(result.dates[2], NaiveDate::from_ymd_opt(2021, 1, 3).unwrap());
-    assert_eq!(result.data[2], vec![5.0, 6.0]);
+    assert_eq!(result.index.label(2), "2021-01-03");
+    assert_eq!(result.row(2), vec![5.0, 6.0]);
 }
 
 #[test]
@@ -38,26 +38,134 @@ fn test_load_with_missing_values() {
 2021-01-03,5.0,6.0
 ";
 
-    let temp_file = NamedTempFile::new().unwrap();
+    let mut temp_file = NamedTempFile::new().unwrap();
     write!(temp_file.as_file_mut(), "{}", csv_content).unwrap();
 
     let result = load::load(temp_file.path().to_str().unwrap()).unwrap();
     
-    assert_eq!(result.dates.len(), 3);
-    assert_eq!(result.data.len(), 3);
+    assert_eq!(result.num_rows, 3);
 
-    assert!(result.data[0][1].is_nan());
-    assert!(result.data[1][0].is_nan());
+    assert!(result.get(0, 1).is_nan());
+    assert!(result.get(1, 0).is_nan());
 }
 
 #[test]
 fn test_main() {
     let csv_content = "DATE,Column1\n2021-01-01,1.0\n";
-    let temp_file = NamedTempFile::new().unwrap();
+    let mut temp_file = NamedTempFile::new().unwrap();
     write!(temp_file.as_file_mut(), "{}", csv_content).unwrap();
 
     let result = load::main(temp_file.path().to_str().unwrap()).unwrap();
-    assert_eq!(result.dates.len(), 1);
-    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.num_rows, 1);
     assert_eq!(result.column_names, vec!["Column1"]);
 }
+
+fn read(csv: &str, options: &CsvOptions) -> DataFrame {
+    DataFrame::from_csv_reader(csv.as_bytes(), options, "row").unwrap()
+}
+
+#[test]
+fn test_quoted_fields_crlf_and_missing_final_newline() {
+    let df = read("DATE,\"Name, with comma\",B\r\n2021-01-01,\"1.5\",2\r\n2021-01-02,3,4", &CsvOptions::default());
+    assert_eq!(df.column_names, vec!["Name, with comma", "B"]);
+    assert_eq!(df.index.labels(), vec!["2021-01-01", "2021-01-02"]);
+    assert_eq!(df.row(0), vec![1.5, 2.0]);
+    assert_eq!(df.row(1), vec![3.0, 4.0]);
+}
+
+#[test]
+fn test_bom_and_blank_trailing_lines() {
+    let df = read("\u{feff}Date,A\n2021-01-01,1\n\n\n", &CsvOptions::default());
+    assert_eq!(df.column_names, vec!["A"]);
+    assert_eq!(df.num_rows, 1);
+}
+
+#[test]
+fn test_delimiter_and_named_index() {
+    let options = CsvOptions::default().with_delimiter(b';').with_index(IndexColumn::Name("day".to_string()));
+    let df = read("A;day;B\n1;2021-01-01;2\n3;2021-01-02;4\n", &options);
+    assert_eq!(df.column_names, vec!["A", "B"]);
+    assert_eq!(df.index.labels(), vec!["2021-01-01", "2021-01-02"]);
+    assert_eq!(df.row(1), vec![3.0, 4.0]);
+
+    let err = DataFrame::from_csv_reader("A;B\n1;2\n".as_bytes(), &options, "row").err().unwrap();
+    assert!(matches!(err, Error::MissingColumn(name) if name == "day"));
+}
+
+#[test]
+fn test_column_layout_matches_row_layout() {
+    let csv = "DATE,A,B,C\n2021-01-01,1,2,3\n2021-01-02,4,5,6\n";
+    let rows = read(csv, &CsvOptions::default());
+    let columns = DataFrame::from_csv_reader(csv.as_bytes(), &CsvOptions::default(), "column").unwrap();
+    for row in 0..2 {
+        assert_eq!(rows.row(row), columns.row(row));
+    }
+}
+
+#[test]
+fn test_load_verb_options() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    write!(temp_file.as_file_mut(), "X\tWhen\n1\t2021-01-01\n").unwrap();
+    let path = temp_file.path().to_str().unwrap().to_string();
+
+    let mut engine = Engine::new();
+    let args = vec![path, "\t".to_string(), "1".to_string()];
+    let df = engine.execute_command("load", &args, None).ok().unwrap();
+    assert_eq!(df.column_names, vec!["X"]);
+    assert_eq!(df.index.labels(), vec!["2021-01-01"]);
+}
+
+#[test]
+fn test_streaming_grows_past_initial_capacity() {
+    // Enough rows to force several reallocations of the builder
+    let mut csv = String::from("DATE,A,B,C\n");
+    for i in 0..5000 {
+        csv.push_str(&format!("r{},{},{},{}\n", i, i, i * 2, -(i as f64)));
+    }
+    for layout in ["row", "column"] {
+        let df = DataFrame::from_csv_reader(csv.as_bytes(), &CsvOptions::default(), layout).unwrap();
+        assert_eq!(df.num_rows, 5000);
+        assert_eq!(df.index.label(4999), "r4999");
+        assert_eq!(df.row(1234), vec![1234.0, 2468.0, -1234.0]);
+        assert_eq!(df.column(2)[4999], -4999.0);
+    }
+}
+
+#[test]
+fn test_push_row_matches_from_rows() {
+    let names = vec!["A".to_string(), "B".to_string()];
+    let rows: Vec<Vec<f64>> = (0..1500).map(|i| vec![i as f64, f64::from(i % 7)]).collect();
+    let row_names: Vec<String> = (0..rows.len()).map(|i| i.to_string()).collect();
+    for layout in ["row", "column"] {
+        let mut builder = DataFrameBuilder::with_columns(names.clone(), layout).unwrap();
+        for (name, row) in row_names.iter().zip(&rows) {
+            builder.push_row(name.clone(), row).unwrap();
+        }
+        let err = builder.push_row("bad".to_string(), &[1.0]).err().unwrap();
+        assert!(matches!(err, Error::Shape(_)));
+
+        let streamed = builder.build().unwrap();
+        let expected = DataFrame::from_rows(row_names.clone(), names.clone(), rows.clone(), layout).unwrap();
+        assert_eq!(streamed.values(), expected.values());
+        assert_eq!(streamed.index, expected.index);
+    }
+}
+
+#[test]
+fn test_binary_reads_piped_stdin() {
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_light-r"))
+        .arg("print")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"DATE,A\n2021-01-01,1.5\n2021-01-02,2.5\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2.5"));
+
+    let empty = Command::new(env!("CARGO_BIN_EXE_light-r")).arg("print").stdin(Stdio::null()).output().unwrap();
+    assert_eq!(empty.status.code(), Some(10));
+}
@@ -0,0 +1,124 @@
+use std::fs;
+
+use light_r::metadata;
+use light_r::operations::{cgrep, dlog, load, save, tags};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error, Tags};
+use tempfile::TempDir;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+fn universe() -> DataFrame {
+    let column_names: Vec<String> = ["SP500", "US10", "BUND", "USDJPY"].iter().map(|s| s.to_string()).collect();
+    let rows = (1..=3).map(|row| (1..=4).map(|col| (row * col) as f64).collect()).collect();
+    DataFrame::from_rows(dates(3), column_names, rows, "column").unwrap()
+}
+
+fn write(dir: &TempDir, name: &str, contents: &str) -> String {
+    let path = dir.path().join(name).to_str().unwrap().to_string();
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+const TAGS_CSV: &str = "column,asset_class,currency,multiplier\nSP500,equity,USD,50\nUS10,rates,USD,1000\nBUND,rates,EUR,\nVIX,vol,USD,1000\n";
+
+fn tag(df: &DataFrame, column: usize, key: &str) -> Option<String> {
+    df.tags[column].get(key).cloned()
+}
+
+#[test]
+fn test_read_csv_and_json_tags() {
+    let dir = TempDir::new().unwrap();
+    let from_csv = metadata::read(&write(&dir, "tags.csv", TAGS_CSV)).unwrap();
+    assert_eq!(from_csv["SP500"]["multiplier"], "50");
+    assert!(!from_csv["BUND"].contains_key("multiplier"));
+
+    let json = r#"{"SP500": {"asset_class": "equity", "currency": "USD", "multiplier": 50, "liquid": true, "sector": null},
+                   "US10": {"asset_class": "rates", "currency": "USD", "multiplier": 1000},
+                   "BUND": {"asset_class": "rates", "currency": "EUR"},
+                   "VIX": {"asset_class": "vol", "currency": "USD", "multiplier": 1000}}"#;
+    let from_json = metadata::read(&write(&dir, "tags.json", json)).unwrap();
+    assert_eq!(from_json["SP500"]["liquid"], "true");
+    assert!(!from_json["SP500"].contains_key("sector"));
+    assert_eq!(from_json["BUND"], from_csv["BUND"]);
+
+    let nested = write(&dir, "bad.json", r#"{"SP500": {"asset_class": ["equity"]}}"#);
+    assert!(matches!(metadata::read(&nested), Err(Error::Format(_))));
+    let broken = write(&dir, "broken.json", "{");
+    assert!(matches!(metadata::read(&broken), Err(Error::Format(_))));
+}
+
+#[test]
+fn test_attach_merges_by_column_name() {
+    let dir = TempDir::new().unwrap();
+    let df = tags::attach_file(&universe(), &write(&dir, "tags.csv", TAGS_CSV)).unwrap();
+    assert_eq!(tag(&df, 0, "asset_class").as_deref(), Some("equity"));
+    assert_eq!(tag(&df, 2, "currency").as_deref(), Some("EUR"));
+    assert_eq!(df.tags[3], Tags::new());
+
+    let more = write(&dir, "more.json", r#"{"BUND": {"currency": "DEM", "sector": "govt"}}"#);
+    let merged = tags::attach_file(&df, &more).unwrap();
+    assert_eq!(tag(&merged, 2, "currency").as_deref(), Some("DEM"));
+    assert_eq!(tag(&merged, 2, "asset_class").as_deref(), Some("rates"));
+}
+
+#[test]
+fn test_load_picks_up_a_sidecar() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("prices.csv").to_str().unwrap().to_string();
+    save::save(&universe(), &path).unwrap();
+    assert!(load::load(&path).unwrap().tags.iter().all(Tags::is_empty));
+
+    write(&dir, "prices.meta.csv", TAGS_CSV);
+    let df = load::load(&path).unwrap();
+    assert_eq!(tag(&df, 1, "multiplier").as_deref(), Some("1000"));
+}
+
+#[test]
+fn test_tags_survive_operations_and_columnar_files() {
+    let dir = TempDir::new().unwrap();
+    let tags_path = write(&dir, "tags.csv", TAGS_CSV);
+    let df = tags::attach_file(&universe(), &tags_path).unwrap();
+    assert_eq!(dlog::dlog(&df).unwrap().tags, df.tags);
+    assert_eq!(df.map(|x| x * 2.0).unwrap().tags, df.tags);
+    assert_eq!(df.slice_rows(1..3).tags, df.tags);
+
+    let rows = (0..3).map(|row| df.row(row)).collect();
+    let by_row = DataFrame::from_rows(df.index.clone(), df.column_names.clone(), rows, "row").unwrap();
+    let row_layout = tags::attach_file(&by_row, &tags_path).unwrap();
+    assert_eq!(dlog::dlog(&row_layout).unwrap().tags, df.tags);
+
+    for name in ["tagged.parquet", "tagged.arrow"] {
+        let path = dir.path().join(name).to_str().unwrap().to_string();
+        save::save(&df, &path).unwrap();
+        assert_eq!(load::load(&path).unwrap().tags, df.tags);
+    }
+}
+
+#[test]
+fn test_cgrep_selects_by_tag() {
+    let dir = TempDir::new().unwrap();
+    let df = tags::attach_file(&universe(), &write(&dir, "tags.csv", TAGS_CSV)).unwrap();
+    let args = |values: &[&str]| values.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    let rates = cgrep::main(&df, &args(&["tag:asset_class=rates"])).unwrap();
+    assert_eq!(rates.column_names, vec!["US10", "BUND"]);
+    assert_eq!(tag(&rates, 1, "currency").as_deref(), Some("EUR"));
+    assert_eq!(cgrep::main(&df, &args(&["tag:multiplier"])).unwrap().column_names, vec!["SP500", "US10"]);
+    assert_eq!(cgrep::main(&df, &args(&["-v", "tag:currency=USD"])).unwrap().column_names, vec!["BUND", "USDJPY"]);
+    assert!(matches!(cgrep::main(&df, &args(&["-s", "tag:asset_class=fx"])), Err(Error::MissingColumn(_))));
+}
+
+#[test]
+fn test_tags_in_a_pipeline() {
+    let dir = TempDir::new().unwrap();
+    let tags_path = write(&dir, "tags.json", r#"{"US10": {"asset_class": "rates"}, "BUND": {"asset_class": "rates"}}"#);
+    let mut engine = Engine::new();
+    engine.set_var("prices", universe());
+    let pipeline = parse_pipeline(&format!("get prices | tags {} | cgrep tag:asset_class=rates | rename BUND RX", tags_path)).unwrap();
+    let df = engine.execute_pipeline(&pipeline, None).unwrap().unwrap();
+    assert_eq!(df.column_names, vec!["US10", "RX"]);
+    assert_eq!(tag(&df, 1, "asset_class").as_deref(), Some("rates"));
+}
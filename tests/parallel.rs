@@ -0,0 +1,94 @@
+use light_r::engine::{self, Engine};
+use light_r::{DataFrame, Error};
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+/// Seven columns, so workers get uneven runs of columns
+fn wide(row_or_column: &str) -> DataFrame {
+    let columns = (0..7).map(|c| format!("C{}", c)).collect();
+    let data = (0..5).map(|r| (0..7).map(|c| (r * 10 + c) as f64).collect()).collect();
+    DataFrame::from_rows(dates(5), columns, data, row_or_column).unwrap()
+}
+
+#[test]
+fn test_every_column_is_processed_once_for_any_thread_count() {
+    for threads in [1, 2, 3, 7, 16] {
+        engine::set_threads(threads);
+        for layout in ["row", "column"] {
+            let df = wide(layout);
+            let result = Engine::parallel_process(&df, |input, output| {
+                for (out, value) in output.iter_mut().zip(input) {
+                    *out = value * 2.0;
+                }
+            })
+            .unwrap();
+            assert_eq!(result.row_or_column, layout);
+            assert_eq!(result.column_names, df.column_names);
+            for row in 0..5 {
+                let expected: Vec<f64> = df.row(row).iter().map(|x| x * 2.0).collect();
+                assert_eq!(result.row(row), expected);
+            }
+        }
+    }
+    engine::set_threads(0);
+    assert!(engine::threads() >= 1);
+}
+
+#[test]
+fn test_output_rows_can_differ_from_input() {
+    let df = wide("row");
+    let result = Engine::parallel_process_into(&df, df.index.slice(4..5), |input, output| {
+        output[0] = input[input.len() - 1];
+    })
+    .unwrap();
+    assert_eq!(result.num_rows, 1);
+    assert_eq!(result.row(0), df.row(4));
+}
+
+#[test]
+fn test_worker_panic_is_an_error() {
+    let df = wide("column");
+    let result = Engine::parallel_process(&df, |input, _| {
+        if input[0] == 6.0 {
+            panic!("bad column");
+        }
+    });
+    assert!(matches!(result, Err(Error::Thread(_))));
+}
+
+#[test]
+fn test_threads_flag() {
+    use std::process::Command;
+
+    let csv = concat!(env!("CARGO_MANIFEST_DIR"), "/data/ALL_FUTURES.csv");
+    let run = |threads: &str| {
+        Command::new(env!("CARGO_BIN_EXE_light-r"))
+            .args(["--threads", threads, &format!("load {} | dlog | ffill", csv)])
+            .output()
+            .unwrap()
+    };
+    assert!(run("2").status.success());
+    assert_eq!(run("0").status.code(), Some(9));
+}
+
+#[test]
+fn test_threads_flag_through_the_lr_wrapper() {
+    use std::process::Command;
+
+    let root = env!("CARGO_MANIFEST_DIR");
+    let run = |threads: &str| {
+        Command::new(format!("{}/lr", root))
+            .current_dir(root)
+            .env("LIGHT_R_BIN", env!("CARGO_BIN_EXE_light-r"))
+            .args(["--threads", threads, "load", "data/ALL_FUTURES.csv", "|", "dlog", "|", "ffill"])
+            .output()
+            .unwrap()
+    };
+    let output = run("2");
+    assert!(output.status.success());
+    // The flag is parsed, not joined into the pipeline
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Command String: load data/ALL_FUTURES.csv | dlog | ffill"));
+    assert_eq!(run("0").status.code(), Some(9));
+}
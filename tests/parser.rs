@@ -0,0 +1,111 @@
+use light_r::parser::{parse_pipeline, parse_script, Arg};
+
+#[test]
+fn test_parse_pipeline() {
+    let pipeline = parse_pipeline("load data/ALL_FUTURES.csv | unitscale 20 0.1 | -> px").unwrap();
+
+    assert_eq!(pipeline.commands.len(), 3);
+    assert_eq!(pipeline.commands[0].name, "load");
+    assert_eq!(pipeline.commands[0].args, vec![Arg::Word("data/ALL_FUTURES.csv".to_string())]);
+    assert_eq!(pipeline.commands[1].args, vec![Arg::Number(20.0, "20".to_string()), Arg::Number(0.1, "0.1".to_string())]);
+    assert_eq!(pipeline.commands[2].name, "->");
+    assert_eq!(pipeline.commands[2].stage, 3);
+    assert_eq!(pipeline.commands[2].span.column, 48);
+}
+
+#[test]
+fn test_parse_quoted_strings() {
+    let pipeline = parse_pipeline(r#"load "my data/prices.csv" | cgrep 'A|B' "say \"hi\"\t""#).unwrap();
+
+    assert_eq!(pipeline.commands.len(), 2);
+    assert_eq!(pipeline.commands[0].args, vec![Arg::Str("my data/prices.csv".to_string())]);
+    assert_eq!(
+        pipeline.commands[1].args,
+        vec![Arg::Str("A|B".to_string()), Arg::Str("say \"hi\"\t".to_string())]
+    );
+}
+
+#[test]
+fn test_parse_escapes_and_variables() {
+    let pipeline = parse_pipeline(r"load my\ file.csv | mult $sig | after 2020-01-01 | shift -1").unwrap();
+
+    assert_eq!(pipeline.commands[0].args, vec![Arg::Word("my file.csv".to_string())]);
+    assert_eq!(pipeline.commands[1].args, vec![Arg::Var("sig".to_string())]);
+    assert_eq!(pipeline.commands[2].args, vec![Arg::Word("2020-01-01".to_string())]);
+    assert_eq!(pipeline.commands[3].args, vec![Arg::Number(-1.0, "-1".to_string())]);
+    assert_eq!(pipeline.commands[3].args[0].text(), "-1");
+}
+
+#[test]
+fn test_numbers_keep_their_text() {
+    let pipeline = parse_pipeline("rename 007 1.50 | mult 1e3").unwrap();
+
+    assert_eq!(pipeline.commands[0].args, vec![Arg::Number(7.0, "007".to_string()), Arg::Number(1.5, "1.50".to_string())]);
+    let texts: Vec<String> = pipeline.commands[0].args.iter().map(Arg::text).collect();
+    assert_eq!(texts, vec!["007", "1.50"]);
+    assert_eq!(pipeline.commands[1].to_string(), "mult 1e3");
+}
+
+#[test]
+fn test_empty_stage_error() {
+    let err = parse_pipeline("dlog || signal").unwrap_err();
+    assert_eq!(err.stage, 2);
+    assert_eq!(err.column, 7);
+    assert_eq!(err.message, "empty pipeline stage");
+
+    let err = parse_pipeline("dlog | signal |").unwrap_err();
+    assert_eq!(err.stage, 3);
+
+    let err = parse_pipeline("   ").unwrap_err();
+    assert_eq!(err.message, "empty pipeline");
+}
+
+#[test]
+fn test_unterminated_quote_error() {
+    let err = parse_pipeline("dlog | load \"oops.csv").unwrap_err();
+    assert_eq!(err.stage, 2);
+    assert_eq!(err.column, 13);
+    assert_eq!(err.message, "unterminated quoted string");
+    assert_eq!(err.render("dlog | load \"oops.csv").lines().last().unwrap(), "            ^");
+}
+
+#[test]
+fn test_invalid_command_name_error() {
+    let err = parse_pipeline("dlog | 42").unwrap_err();
+    assert_eq!(err.stage, 2);
+    assert!(err.message.contains("expected a command name"));
+
+    let err = parse_pipeline("\"load\" x.csv").unwrap_err();
+    assert_eq!(err.stage, 1);
+}
+
+#[test]
+fn test_multiline_positions() {
+    let err = parse_pipeline("load x.csv |\n  dlog \\q").unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(err.column, 8);
+    assert_eq!(err.stage, 2);
+}
+
+#[test]
+fn test_parse_script() {
+    let source = "# load prices\nload px.csv | -> px   # trailing comment\n\n   \nget px | cgrep \\#1 '#2'\n";
+    let pipelines = parse_script(source).unwrap();
+
+    assert_eq!(pipelines.len(), 2);
+    assert_eq!(pipelines[0].commands[0].span.line, 2);
+    assert_eq!(pipelines[0].commands[1].args, vec![Arg::Word("px".to_string())]);
+    assert_eq!(pipelines[1].commands[0].span.line, 5);
+    assert_eq!(
+        pipelines[1].commands[1].args,
+        vec![Arg::Word("#1".to_string()), Arg::Str("#2".to_string())]
+    );
+}
+
+#[test]
+fn test_parse_script_error_line() {
+    let err = parse_script("load px.csv\n\n# ok so far\ndlog | | signal\n").unwrap_err();
+    assert_eq!(err.line, 4);
+    assert_eq!(err.column, 8);
+    assert_eq!(err.stage, 2);
+}
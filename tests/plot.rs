@@ -1,45 +1,42 @@
 use light_r::{DataFrame, operations::plot};
-use chrono::NaiveDate;
 use std::fs;
 
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
 #[test]
 fn test_plot() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 3).unwrap(),
-    ];
     let data = vec![
         vec![1.0, 2.0],
         vec![2.0, 3.0],
-        vec![3.0, 4.0],
+        vec![3.0, f64::NAN],
     ];
     let column_names = vec!["A".to_string(), "B".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    let df = DataFrame::from_rows(dates(3), column_names, data, "row").unwrap();
 
-    let result = plot::plot(&df);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plot.html");
+    let result = plot::plot(&df, path.to_str().unwrap());
     assert!(result.is_ok());
 
-    // Check if the file was created
-    assert!(fs::metadata("plot.html").is_ok());
-
-    // Clean up
-    fs::remove_file("plot.html").unwrap();
+    // Check the file was created and missing values are emitted as null
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("name: 'A'"));
+    assert!(contents.contains("y: [2,3,null]"));
 }
 
 #[test]
 fn test_main() {
-    let dates = vec![NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()];
     let data = vec![vec![1.0]];
     let column_names = vec!["A".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    let df = DataFrame::from_rows(dates(1), column_names, data, "column").unwrap();
 
-    let result = plot::main(&df);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plot.html");
+    let result = plot::main(&df, path.to_str().unwrap());
     assert!(result.is_ok());
 
     // Check if the file was created
-    assert!(fs::metadata("plot.html").is_ok());
-
-    // Clean up
-    fs::remove_file("plot.html").unwrap();
+    assert!(fs::metadata(&path).is_ok());
 }
@@ -0,0 +1,72 @@
+use light_r::registry::{ArgKind, ArgSpec, Args};
+use light_r::{DataFrame, Engine, Operation, Registry, Result};
+
+/// A user-defined operation scaling every value by a factor
+struct Scale;
+
+impl Operation for Scale {
+    fn name(&self) -> &str {
+        "scale"
+    }
+
+    fn help(&self) -> &str {
+        "Multiply every value by a factor"
+    }
+
+    fn args(&self) -> &[ArgSpec] {
+        const ARGS: &[ArgSpec] = &[ArgSpec::with_default("factor", ArgKind::Number, "2")];
+        ARGS
+    }
+
+    fn apply(&self, inputs: &[DataFrame], args: &Args) -> Result<DataFrame> {
+        let factor: f64 = args.parse(0)?;
+        let values: Vec<f64> = (0..inputs[0].num_rows)
+            .flat_map(|row| inputs[0].row(row))
+            .map(|x| x * factor)
+            .collect();
+        inputs[0].with_values(&values)
+    }
+}
+
+fn sample_df() -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
+    DataFrame::from_rows(dates, vec!["A".to_string()], vec![vec![1.0], vec![-2.0]], "row").unwrap()
+}
+
+#[test]
+fn test_register_custom_operation() {
+    let mut engine = Engine::new();
+    engine.register(Scale);
+
+    assert!(engine.command_names().contains(&"scale".to_string()));
+
+    let result = engine.execute_command("scale", &[], Some(sample_df())).unwrap();
+    assert_eq!(result.row(1), vec![-4.0]);
+
+    let result = engine.execute_command("scale", &["0.5".to_string()], Some(sample_df())).unwrap();
+    assert_eq!(result.row(0), vec![0.5]);
+}
+
+#[test]
+fn test_custom_registry_replaces_builtins() {
+    let mut registry = Registry::new();
+    registry.register(Scale);
+    let mut engine = Engine::with_registry(registry);
+
+    assert!(engine.execute_command("signal", &[], Some(sample_df())).is_err());
+    assert!(engine.execute_command("scale", &[], Some(sample_df())).is_ok());
+}
+
+#[test]
+fn test_builtins_declare_metadata() {
+    let registry = Registry::with_builtins();
+
+    for op in registry.iter() {
+        assert!(!op.help().is_empty(), "{} has no help text", op.name());
+    }
+    let unitscale = registry.get("unitscale").unwrap();
+    assert_eq!(unitscale.args().len(), 2);
+    assert_eq!(unitscale.args()[0].kind, ArgKind::Count);
+    assert_eq!(registry.get("mult").unwrap().arity(), 2);
+    assert_eq!(registry.get("load").unwrap().arity(), 0);
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use light_r::{DataFrame, Error, operations::rename};
+
+fn sample_df() -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
+    let column_names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    DataFrame::from_rows(dates, column_names, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]], "row").unwrap()
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_rename_pairs() {
+    let df = sample_df();
+    let result = rename::main(&df, &args(&["A", "SP500", "C", "US10"])).unwrap();
+    assert_eq!(result.column_names, vec!["SP500", "B", "US10"]);
+    assert_eq!(result.row(1), vec![4.0, 5.0, 6.0]);
+    assert!(Arc::ptr_eq(&result.mmap, &df.mmap));
+}
+
+#[test]
+fn test_swap_names() {
+    let result = rename::main(&sample_df(), &args(&["A", "B", "B", "A"])).unwrap();
+    assert_eq!(result.column_names, vec!["B", "A", "C"]);
+}
+
+#[test]
+fn test_rename_errors() {
+    let df = sample_df();
+    assert!(matches!(rename::main(&df, &args(&["X", "Y"])), Err(Error::MissingColumn(name)) if name == "X"));
+    assert!(matches!(rename::main(&df, &args(&["A", "B"])), Err(Error::InvalidValue(_))));
+    assert!(matches!(rename::main(&df, &args(&["A"])), Err(Error::InvalidValue(_))));
+}
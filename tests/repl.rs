@@ -0,0 +1,37 @@
+use light_r::repl::{eval_line, ReplHelper};
+use light_r::{DataFrame, Engine};
+
+fn sample_df() -> DataFrame {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
+    let column_names = vec!["SP500".to_string(), "US2".to_string()];
+    DataFrame::from_rows(dates, column_names, vec![vec![1.0, -2.0], vec![3.0, 4.0]], "row").unwrap()
+}
+
+#[test]
+fn test_eval_line_keeps_state() {
+    let mut engine = Engine::new();
+
+    let df = eval_line(&mut engine, "signal | -> sig", Some(sample_df())).unwrap();
+    let df = eval_line(&mut engine, "cgrep US2", df).unwrap().unwrap();
+    assert_eq!(df.column_names, vec!["US2"]);
+    assert_eq!(engine.var("sig").unwrap().row(0), vec![1.0, -1.0]);
+
+    // A failing line reports an error and leaves the caller's frame alone
+    assert!(eval_line(&mut engine, "shift x", Some(df.clone())).is_err());
+    assert!(eval_line(&mut engine, "cgrep \"US2", Some(df)).is_err());
+}
+
+#[test]
+fn test_completion_candidates() {
+    let mut engine = Engine::new();
+    engine.set_var("prices", sample_df());
+
+    let mut helper = ReplHelper::default();
+    helper.refresh(&engine, None);
+
+    assert_eq!(helper.candidates("sh", 2), (0, vec!["shift".to_string()]));
+    assert_eq!(helper.candidates("dlog | si", 9), (7, vec!["signal".to_string()]));
+    assert_eq!(helper.candidates("cgrep S", 7), (6, vec!["SP500".to_string()]));
+    assert_eq!(helper.candidates("mult $p", 7), (5, vec!["$prices".to_string()]));
+    assert_eq!(helper.candidates("get pr", 6), (4, vec!["prices".to_string()]));
+}
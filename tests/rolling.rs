@@ -0,0 +1,217 @@
+use light_r::operations::rolling::{self, Stat, Window};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error};
+use approx::assert_relative_eq;
+use proptest::prelude::*;
+
+fn dates(n: usize) -> Vec<String> {
+    (1..=n).map(|d| format!("2021-01-{:02}", d)).collect()
+}
+
+const ALL: &[Stat] = &[
+    Stat::Mean,
+    Stat::Std,
+    Stat::Sum,
+    Stat::Min,
+    Stat::Max,
+    Stat::Median,
+    Stat::Quantile(0.25),
+    Stat::Skew,
+    Stat::Zscore,
+];
+
+/// The statistic recomputed from scratch over each window
+fn naive(values: &[f64], stat: Stat, window: Window) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window.size);
+            let mut xs: Vec<f64> = values[start..=i].iter().copied().filter(|x| !x.is_nan()).collect();
+            xs.sort_by(f64::total_cmp);
+            let n = xs.len() as f64;
+            let least = match stat {
+                Stat::Std | Stat::Zscore => 2,
+                Stat::Skew => 3,
+                _ => 1,
+            };
+            if xs.len() < window.min_periods.max(least) {
+                return f64::NAN;
+            }
+            let mean = xs.iter().sum::<f64>() / n;
+            let central = |power: i32| xs.iter().map(|x| (x - mean).powi(power)).sum::<f64>();
+            let quantile = |q: f64| {
+                let position = q * (n - 1.0);
+                let (below, above) = (position.floor() as usize, position.ceil() as usize);
+                xs[below] + (xs[above] - xs[below]) * (position - below as f64)
+            };
+            match stat {
+                Stat::Mean => mean,
+                Stat::Std => (central(2) / (n - 1.0)).sqrt(),
+                Stat::Sum => xs.iter().sum(),
+                Stat::Min => xs[0],
+                Stat::Max => xs[xs.len() - 1],
+                Stat::Median => quantile(0.5),
+                Stat::Quantile(q) => quantile(q),
+                Stat::Skew => {
+                    let (b, c) = (central(2) / n, central(3) / n);
+                    if b == 0.0 { f64::NAN } else { (n * (n - 1.0)).sqrt() / (n - 2.0) * c / b.powf(1.5) }
+                }
+                Stat::Zscore => {
+                    let std = (central(2) / (n - 1.0)).sqrt();
+                    if std == 0.0 { f64::NAN } else { (values[i] - mean) / std }
+                }
+            }
+        })
+        .collect()
+}
+
+fn kernel(values: &[f64], stat: Stat, window: Window) -> Vec<f64> {
+    let mut out = vec![0.0; values.len()];
+    rolling::rolling_column(values, &mut out, stat, window);
+    out
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a.is_nan() && b.is_nan()) || (a - b).abs() <= 1e-8 * (1.0 + a.abs().max(b.abs()))
+}
+
+fn sample() -> DataFrame {
+    let x = [1.0, 3.0, 2.0, f64::NAN, 5.0, 4.0, 8.0];
+    let rows = x.iter().map(|&x| vec![x, -x]).collect();
+    DataFrame::from_rows(dates(7), vec!["A".to_string(), "B".to_string()], rows, "row").unwrap()
+}
+
+#[test]
+fn test_known_values() {
+    let df = sample();
+    let window = Window::new(3, Some(2)).unwrap();
+    let stat = |stat: Stat| rolling::rolling(&df, stat, window).unwrap();
+
+    let mean = stat(Stat::Mean);
+    assert!(mean.get(0, 0).is_nan());
+    assert_eq!(mean.column(0)[1..6].to_vec(), vec![2.0, 2.0, 2.5, 3.5, 4.5]);
+    assert_relative_eq!(mean.get(6, 0), 17.0 / 3.0);
+
+    let std = stat(Stat::Std);
+    assert_relative_eq!(std.get(1, 0), 2f64.sqrt(), epsilon = 1e-12);
+    assert_relative_eq!(std.get(2, 0), 1.0, epsilon = 1e-12);
+    assert_relative_eq!(std.get(6, 0), 2.0816660, epsilon = 1e-6);
+
+    assert_relative_eq!(stat(Stat::Skew).get(6, 0), 1.2933427, epsilon = 1e-6);
+    assert_relative_eq!(stat(Stat::Skew).get(6, 1), -1.2933427, epsilon = 1e-6);
+    assert_relative_eq!(stat(Stat::Zscore).get(6, 0), 1.1208971, epsilon = 1e-6);
+    assert_eq!(stat(Stat::Sum).column(0)[1..].to_vec(), vec![4.0, 6.0, 5.0, 7.0, 9.0, 17.0]);
+    assert_eq!(stat(Stat::Min).column(0)[1..].to_vec(), vec![1.0, 1.0, 2.0, 2.0, 4.0, 4.0]);
+    assert_eq!(stat(Stat::Max).column(1)[1..].to_vec(), vec![-1.0, -1.0, -2.0, -2.0, -4.0, -4.0]);
+    assert_eq!(stat(Stat::Median).get(6, 0), 5.0);
+    assert_eq!(stat(Stat::Quantile(0.25)).get(6, 0), 4.5);
+
+    // Layout and index carry through
+    assert_eq!(mean.row_or_column, "row");
+    assert_eq!(mean.index, df.index);
+}
+
+#[test]
+fn test_min_periods_defaults_to_the_window() {
+    let df = sample();
+    let full = rolling::main(&df, Stat::Mean, 3, None).unwrap();
+    let expected = [f64::NAN, f64::NAN, 2.0, f64::NAN, f64::NAN, f64::NAN, 17.0 / 3.0];
+    assert!(full.column(0).iter().zip(expected).all(|(&a, b)| close(a, b)));
+}
+
+#[test]
+fn test_invalid_windows() {
+    let df = sample();
+    assert!(matches!(rolling::main(&df, Stat::Mean, 0, None), Err(Error::InvalidValue(_))));
+    assert!(matches!(rolling::main(&df, Stat::Mean, 3, Some(0)), Err(Error::InvalidValue(_))));
+    assert!(matches!(rolling::main(&df, Stat::Mean, 3, Some(4)), Err(Error::InvalidValue(_))));
+    assert!(matches!(rolling::main(&df, Stat::Quantile(1.5), 3, None), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_long_series_stays_accurate() {
+    // Large offsets and many updates would drift without compensation
+    let values: Vec<f64> = (0..100_000).map(|i| 1e6 + ((i * 7919) % 1000) as f64 / 10.0).collect();
+    let window = Window::new(50, None).unwrap();
+    for stat in [Stat::Mean, Stat::Std, Stat::Sum] {
+        let (fast, slow) = (kernel(&values, stat, window), naive(&values[99_000..], stat, window));
+        assert!(fast[99_049..].iter().zip(&slow[49..]).all(|(&a, &b)| close(a, b)), "{:?}", stat);
+    }
+}
+
+#[test]
+fn test_infinities_leave_the_window() {
+    let inf = f64::INFINITY;
+    let values = [1.0, inf, 1.0, 1.0, -inf, inf, 1.0, 2.0];
+    let window = Window::new(2, None).unwrap();
+    let sums = kernel(&values, Stat::Sum, window);
+    let expected = [f64::NAN, inf, inf, 2.0, -inf, f64::NAN, inf, 3.0];
+    assert!(sums.iter().zip(expected).all(|(&a, b)| a == b || (a.is_nan() && b.is_nan())), "{:?}", sums);
+    let means = kernel(&values, Stat::Mean, window);
+    assert_eq!((means[2], means[3], means[7]), (inf, 1.0, 1.5));
+    // The other kernels recover once the infinity has left too
+    for stat in [Stat::Std, Stat::Min, Stat::Max, Stat::Median] {
+        let (fast, slow) = (kernel(&values, stat, window), naive(&values, stat, window));
+        assert!(close(fast[7], slow[7]) && close(fast[3], slow[3]), "{:?}", stat);
+    }
+}
+
+#[test]
+fn test_decaying_and_constant_series_stay_accurate() {
+    // Each value dwarfs the rest of the window once it has left; removal must not cancel
+    let decaying: Vec<f64> = (0..400).map(|i| 0.9f64.powi(i) * if i % 2 == 0 { 1.0 } else { -0.5 }).collect();
+    let window = Window::new(20, None).unwrap();
+    for stat in [Stat::Std, Stat::Skew, Stat::Zscore] {
+        let (fast, slow) = (kernel(&decaying, stat, window), naive(&decaying, stat, window));
+        for (i, (&a, &b)) in fast.iter().zip(&slow).enumerate() {
+            assert!((a.is_nan() && b.is_nan()) || (a - b).abs() <= 1e-9 * b.abs().max(1e-300), "{:?} at row {}: {} vs {}", stat, i, a, b);
+        }
+    }
+
+    let flat: Vec<f64> = [1e9, -3.0, 7.5].into_iter().chain(std::iter::repeat_n(0.1, 20)).collect();
+    let std = kernel(&flat, Stat::Std, Window::new(5, None).unwrap());
+    assert!(std[7..].iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn test_wide_windows() {
+    let values: Vec<f64> = (0..20_000).map(|i| ((i * 7919) % 10_007) as f64).collect();
+    let window = Window::new(5_000, None).unwrap();
+    let mut last: Vec<f64> = values[15_000..].to_vec();
+    last.sort_by(f64::total_cmp);
+    let mean = last.iter().sum::<f64>() / 5_000.0;
+    let std = (last.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 4_999.0).sqrt();
+    let expected = [(Stat::Median, (last[2_499] + last[2_500]) / 2.0), (Stat::Quantile(0.9), last[4_499] + (last[4_500] - last[4_499]) * 0.1), (Stat::Std, std)];
+    for (stat, expected) in expected {
+        assert!(close(kernel(&values, stat, window)[19_999], expected), "{:?}", stat);
+    }
+}
+
+#[test]
+fn test_rolling_verbs_in_a_pipeline() {
+    let mut engine = Engine::new();
+    engine.set_var("x", sample());
+    let run = |engine: &mut Engine, line: &str| engine.execute_pipeline(&parse_pipeline(line).unwrap(), None).unwrap().unwrap();
+
+    assert_eq!(run(&mut engine, "get x | rmax 3 2").get(4, 0), 5.0);
+    assert_eq!(run(&mut engine, "get x | rquantile 3 0.25 min_periods=2").get(6, 0), 4.5);
+    assert!(run(&mut engine, "get x | rmedian 3").get(4, 0).is_nan());
+    let std = engine.execute_pipeline(&parse_pipeline("get x | rstd 3 5").unwrap(), None);
+    assert!(matches!(std.err().unwrap().root(), Error::InvalidValue(_)));
+}
+
+proptest! {
+    #[test]
+    fn kernels_match_recomputing_each_window(
+        values in prop::collection::vec(prop_oneof![1 => Just(f64::NAN), 4 => (-1000i32..1000).prop_map(|x| f64::from(x) / 8.0)], 0..60),
+        size in 1usize..12,
+        min_periods in 1usize..12,
+    ) {
+        let window = Window::new(size, Some(min_periods.min(size))).unwrap();
+        for &stat in ALL {
+            let (fast, slow) = (kernel(&values, stat, window), naive(&values, stat, window));
+            for (i, (&a, &b)) in fast.iter().zip(&slow).enumerate() {
+                prop_assert!(close(a, b), "{:?} at row {}: {} vs {}", stat, i, a, b);
+            }
+        }
+    }
+}
@@ -1,20 +1,16 @@
-use light_r::{DataFrame, operations::save};
-use chrono::NaiveDate;
+use light_r::{DataFrame, operations::{load, save}};
 use std::fs;
 use std::io::Read;
 
 #[test]
 fn test_save() {
-    let dates = vec![
-        NaiveDate::from_ymd_opt(2021, 1, 1).unwrap(),
-        NaiveDate::from_ymd_opt(2021, 1, 2).unwrap(),
-    ];
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
     let data = vec![
         vec![1.0, 2.0],
         vec![3.0, 4.0],
     ];
     let column_names = vec!["A".to_string(), "B".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    let df = DataFrame::from_rows(dates, column_names, data, "column").unwrap();
 
     let filename = "test_save.csv";
     let result = save::save(&df, filename);
@@ -35,14 +31,15 @@ fn test_save() {
 
 #[test]
 fn test_main() {
-    let dates = vec![NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()];
+    let dates = vec!["2021-01-01".to_string()];
     let data = vec![vec![1.0]];
     let column_names = vec!["A".to_string()];
-    let df = DataFrame { dates, data, column_names };
+    let df = DataFrame::from_rows(dates, column_names, data, "row").unwrap();
 
     let filename = "test_main.csv";
     let result = save::main(&df, filename);
     assert!(result.is_ok());
+    assert_eq!(result.unwrap().num_rows, 1);
 
     // Check if the file was created
     assert!(fs::metadata(filename).is_ok());
@@ -50,3 +47,21 @@ fn test_main() {
     // Clean up
     fs::remove_file(filename).unwrap();
 }
+
+#[test]
+fn test_names_with_commas_and_quotes_round_trip() {
+    let dates = vec!["2021-01-01".to_string(), "2021-01-02".to_string()];
+    let column_names = vec!["CL,1".to_string(), "say \"hi\"".to_string()];
+    let df = DataFrame::from_rows(dates, column_names, vec![vec![1.0, f64::NAN], vec![3.5, 4.0]], "column").unwrap();
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let filename = dir.path().join("quoted.csv").to_str().unwrap().to_string();
+    save::save(&df, &filename).unwrap();
+    assert!(fs::read_to_string(&filename).unwrap().starts_with("DATE,\"CL,1\",\"say \"\"hi\"\"\"\n"));
+
+    let loaded = load::load(&filename).unwrap();
+    assert_eq!(loaded.column_names, df.column_names);
+    assert_eq!(loaded.index, df.index);
+    assert_eq!(loaded.row(1), df.row(1));
+    assert!(loaded.get(0, 1).is_nan());
+}
@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate};
+use light_r::operations::slice::{self, Selector};
+use light_r::operations::{cumsum, save_bin, load_bin};
+use light_r::parser::parse_pipeline;
+use light_r::{DataFrame, Engine, Error};
+use tempfile::TempDir;
+
+/// One row a day from 2019-12-30, valued by row number in column A and its negative in B
+fn daily(n: usize, row_or_column: &str) -> DataFrame {
+    let start = NaiveDate::from_ymd_opt(2019, 12, 30).unwrap();
+    let dates: Vec<NaiveDate> = (0..n as i64).map(|i| start + Duration::days(i)).collect();
+    let rows = (0..n).map(|i| vec![i as f64, -(i as f64)]).collect();
+    DataFrame::from_rows(dates, vec!["A".to_string(), "B".to_string()], rows, row_or_column).unwrap()
+}
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn first_and_last(df: &DataFrame) -> (String, String) {
+    (df.index.label(0), df.index.label(df.num_rows - 1))
+}
+
+#[test]
+fn test_parse_selectors() {
+    assert_eq!("after 2020-01-31".parse::<Selector>().unwrap(), Selector::After(date("2020-01-31")));
+    assert_eq!("before 2020-01-31".parse::<Selector>().unwrap(), Selector::Before(date("2020-01-31")));
+    assert_eq!(
+        "between 2020-01-01 2020-02-01".parse::<Selector>().unwrap(),
+        Selector::Between(date("2020-01-01"), date("2020-02-01"))
+    );
+    assert_eq!("last 3y".parse::<Selector>().unwrap(), Selector::LastMonths(36));
+    assert_eq!("last 6m".parse::<Selector>().unwrap(), Selector::LastMonths(6));
+    assert_eq!("last 2w".parse::<Selector>().unwrap(), Selector::LastDays(14));
+    assert_eq!("last 250".parse::<Selector>().unwrap(), Selector::LastRows(250));
+    assert_eq!("2008".parse::<Selector>().unwrap(), Selector::Year(2008));
+    assert_eq!("2020-03".parse::<Selector>().unwrap(), Selector::Month(2020, 3));
+
+    for bad in ["", "last", "last 3q", "last y", "2020-13", "20-03", "between 2020-01-01", "after 01/02/2020"] {
+        assert!(matches!(bad.parse::<Selector>(), Err(Error::InvalidValue(_))), "{:?} should not parse", bad);
+    }
+}
+
+#[test]
+fn test_calendar_selectors_in_both_layouts() {
+    for layout in ["row", "column"] {
+        let df = daily(500, layout);
+        let year = slice::main(&df, "2020").unwrap();
+        assert_eq!(first_and_last(&year), ("2020-01-01".to_string(), "2020-12-31".to_string()));
+        assert_eq!(year.num_rows, 366);
+        assert_eq!(year.row(0), vec![2.0, -2.0]);
+
+        let month = slice::main(&df, "2020-02").unwrap();
+        assert_eq!(month.num_rows, 29);
+        assert_eq!(month.column(1)[28], -(31.0 + 29.0 + 1.0));
+
+        let between = slice::main(&df, "between 2020-03-01 2020-03-03").unwrap();
+        assert_eq!(between.column(0).to_vec(), vec![62.0, 63.0, 64.0]);
+
+        assert_eq!(slice::main(&df, "after 2021-05-10").unwrap().num_rows, 2);
+        assert_eq!(slice::main(&df, "before 2020-01-01").unwrap().num_rows, 2);
+        assert_eq!(slice::main(&df, "1999").unwrap().num_rows, 0);
+        assert_eq!(slice::main(&df, "between 2020-03-03 2020-03-01").unwrap().num_rows, 0);
+    }
+}
+
+#[test]
+fn test_relative_selectors_count_back_from_the_last_row() {
+    let df = daily(1200, "column");
+    assert_eq!(df.index.label(1199), "2023-04-12");
+
+    let years = slice::main(&df, "last 3y").unwrap();
+    assert_eq!(first_and_last(&years), ("2020-04-13".to_string(), "2023-04-12".to_string()));
+    let months = slice::main(&df, "last 1m").unwrap();
+    assert_eq!(months.index.label(0), "2023-03-13");
+    assert_eq!(slice::main(&df, "last 2w").unwrap().num_rows, 14);
+    assert_eq!(slice::main(&df, "last 250").unwrap().num_rows, 250);
+    assert_eq!(slice::main(&df, "last 5000").unwrap().num_rows, 1200);
+    assert_eq!(slice::main(&df, "last 50y").unwrap().num_rows, 1200);
+}
+
+#[test]
+fn test_slices_are_views_of_the_parent_mapping() {
+    let df = daily(100, "column");
+    let view = slice::main(&df, "between 2020-01-10 2020-01-19").unwrap();
+    assert!(Arc::ptr_eq(&view.mmap, &df.mmap));
+    assert!(matches!(view.column(1), Cow::Borrowed(_)));
+    let expected: Vec<f64> = (11..21).map(f64::from).chain((11..21).map(|i| -f64::from(i))).collect();
+    assert_eq!(view.values().to_vec(), expected);
+
+    // A slice of a slice is still a view, and operations read it like any other frame
+    let inner = slice::main(&view, "last 3").unwrap();
+    assert!(Arc::ptr_eq(&inner.mmap, &df.mmap));
+    assert_eq!(inner.row(0), vec![18.0, -18.0]);
+    let summed = cumsum::cumulative_sum(&inner, 0.0).unwrap();
+    assert_eq!(summed.column(0).to_vec(), vec![18.0, 37.0, 57.0]);
+}
+
+#[test]
+fn test_saving_a_view_writes_only_its_rows() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("view.bin").to_str().unwrap().to_string();
+    for layout in ["row", "column"] {
+        let view = slice::main(&daily(40, layout), "2020-01").unwrap();
+        save_bin::save_bin(&view, &path).unwrap();
+        let loaded = load_bin::load_bin(&path).unwrap();
+        assert_eq!(loaded.index, view.index);
+        assert_eq!(loaded.values(), view.values());
+    }
+}
+
+#[test]
+fn test_label_index_only_supports_row_counts() {
+    let df = DataFrame::from_rows(
+        vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        vec!["X".to_string()],
+        vec![vec![1.0], vec![2.0], vec![3.0]],
+        "row",
+    )
+    .unwrap();
+    assert_eq!(slice::main(&df, "last 2").unwrap().index.labels(), vec!["b", "c"]);
+    assert!(matches!(slice::main(&df, "2020"), Err(Error::InvalidValue(_))));
+    assert!(matches!(slice::main(&df, "last 1y"), Err(Error::InvalidValue(_))));
+}
+
+#[test]
+fn test_slice_in_a_pipeline() {
+    let mut engine = Engine::new();
+    engine.set_var("prices", daily(100, "column"));
+    let pipeline = parse_pipeline("get prices | slice 2020-03").unwrap();
+    let df = engine.execute_pipeline(&pipeline, None).unwrap().unwrap();
+    assert_eq!(first_and_last(&df), ("2020-03-01".to_string(), "2020-03-31".to_string()));
+}